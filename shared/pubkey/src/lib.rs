@@ -0,0 +1,177 @@
+// aim: 계정 식별자를 String 대신 Solana와 같은 32바이트 공개키로 표현한다.
+// String 키는 clone할 때마다 힙 할당이 일어나고 아무 문자열이나 받아들이지만,
+// Pubkey는 Copy 가능한 고정 크기 값이라 잠금/맵 키로 그대로 복사해 쓸 수 있다.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub const PUBKEY_BYTES: usize = 32;
+
+// base58 문자열 길이 상한. 32바이트는 base58로 최대 44글자이다.
+const MAX_BASE58_LEN: usize = 44;
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+// 32바이트 공개키. Hash/Ord를 파생해 HashMap, BTreeMap 키로 쓸 수 있다.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Pubkey([u8; PUBKEY_BYTES]);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParsePubkeyError {
+    // base58 문자열이 너무 길거나, 디코딩 결과가 32바이트가 아니다.
+    WrongSize,
+    // base58 알파벳에 없는 문자가 들어 있다. (0, O, I, l 등)
+    InvalidCharacter { character: char, index: usize },
+}
+
+impl fmt::Display for ParsePubkeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsePubkeyError::WrongSize => write!(f, "pubkey must decode to {PUBKEY_BYTES} bytes"),
+            ParsePubkeyError::InvalidCharacter { character, index } => {
+                write!(f, "invalid base58 character {character:?} at index {index}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParsePubkeyError {}
+
+impl Pubkey {
+    pub const fn new_from_array(bytes: [u8; PUBKEY_BYTES]) -> Self {
+        Self(bytes)
+    }
+
+    // 테스트용 고유 키. 프로세스 안에서 호출 순서대로 1, 2, 3...을 앞 8바이트에 빅엔디언으로 적는다.
+    // 같은 순서로 호출하면 항상 같은 키가 나오므로 결과가 결정적이다.
+    pub fn new_unique() -> Self {
+        static COUNTER: AtomicU64 = AtomicU64::new(1);
+        let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let mut bytes = [0u8; PUBKEY_BYTES];
+        bytes[..8].copy_from_slice(&id.to_be_bytes());
+        Self(bytes)
+    }
+
+    // 테스트용 이름 키. 32바이트 이하의 이름은 바이트를 그대로 앞부분에 채워 base58 없이도 읽을 수 있게 하고,
+    // 더 긴 이름은 FNV-1a 해시 네 개(8바이트씩)로 32바이트를 채운다. 같은 이름은 항상 같은 키가 된다.
+    pub fn from_name(name: &str) -> Self {
+        let mut bytes = [0u8; PUBKEY_BYTES];
+        if name.len() <= PUBKEY_BYTES {
+            bytes[..name.len()].copy_from_slice(name.as_bytes());
+            return Self(bytes);
+        }
+        for (lane, chunk) in bytes.chunks_exact_mut(8).enumerate() {
+            let mut hash: u64 = 0xcbf2_9ce4_8422_2325 ^ lane as u64;
+            for &byte in name.as_bytes() {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+            }
+            chunk.copy_from_slice(&hash.to_be_bytes());
+        }
+        Self(bytes)
+    }
+
+    pub const fn to_bytes(self) -> [u8; PUBKEY_BYTES] {
+        self.0
+    }
+
+    pub fn as_bytes(&self) -> &[u8; PUBKEY_BYTES] {
+        &self.0
+    }
+}
+
+impl From<[u8; PUBKEY_BYTES]> for Pubkey {
+    fn from(bytes: [u8; PUBKEY_BYTES]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl AsRef<[u8]> for Pubkey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl FromStr for Pubkey {
+    type Err = ParsePubkeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() > MAX_BASE58_LEN {
+            return Err(ParsePubkeyError::WrongSize);
+        }
+        let decoded = decode_base58(s)?;
+        let bytes: [u8; PUBKEY_BYTES] = decoded
+            .try_into()
+            .map_err(|_| ParsePubkeyError::WrongSize)?;
+        Ok(Self(bytes))
+    }
+}
+
+impl fmt::Display for Pubkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&encode_base58(&self.0))
+    }
+}
+
+// 로그와 assert 메시지에서 바이트 배열 대신 base58로 보이도록 Debug도 같은 표현을 쓴다.
+impl fmt::Debug for Pubkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Pubkey({self})")
+    }
+}
+
+// base58 인코딩: 바이트열을 큰 정수로 보고 58진수로 바꾼다. 앞의 0 바이트는 '1'로 하나씩 보존한다.
+fn encode_base58(input: &[u8]) -> String {
+    let zeros = input.iter().take_while(|&&b| b == 0).count();
+    // 58진수 자릿수를 작은 자리부터 저장한다.
+    let mut digits: Vec<u8> = Vec::with_capacity(input.len() * 138 / 100 + 1);
+    for &byte in &input[zeros..] {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let mut out = String::with_capacity(zeros + digits.len());
+    out.extend(std::iter::repeat_n('1', zeros));
+    out.extend(
+        digits
+            .iter()
+            .rev()
+            .map(|&d| BASE58_ALPHABET[d as usize] as char),
+    );
+    out
+}
+
+fn decode_base58(input: &str) -> Result<Vec<u8>, ParsePubkeyError> {
+    let zeros = input.bytes().take_while(|&c| c == b'1').count();
+    // 256진수 자릿수를 작은 자리부터 저장한다.
+    let mut bytes: Vec<u8> = Vec::with_capacity(input.len());
+    for (index, character) in input.chars().enumerate().skip(zeros) {
+        let value = BASE58_ALPHABET
+            .iter()
+            .position(|&a| a as char == character)
+            .ok_or(ParsePubkeyError::InvalidCharacter { character, index })?;
+        let mut carry = value as u32;
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = (carry & 0xff) as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push((carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+
+    let mut out = vec![0u8; zeros];
+    out.extend(bytes.iter().rev());
+    Ok(out)
+}
@@ -0,0 +1,76 @@
+// 이 테스트 모음은 Pubkey의 base58 인코딩/파싱과 정렬, 고유 키 생성을 확인한다.
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use pubkey::{ParsePubkeyError, Pubkey};
+
+// 시스템 프로그램 주소(모두 0인 키)는 '1' 32개로 표현된다.
+#[test]
+fn test_zero_key_encodes_as_ones() {
+    let zero = Pubkey::default();
+    assert_eq!(zero.to_string(), "1".repeat(32));
+    assert_eq!(Pubkey::from_str(&"1".repeat(32)), Ok(zero));
+}
+
+// 잘 알려진 Solana 주소를 파싱하고 다시 인코딩하면 원래 문자열이 나온다.
+#[test]
+fn test_known_address_round_trip() {
+    let token_program = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+    let key = Pubkey::from_str(token_program).unwrap();
+    assert_eq!(key.to_string(), token_program);
+    assert_eq!(key.as_bytes()[0], 6);
+}
+
+// 임의의 바이트 배열도 인코딩 → 파싱으로 되돌아온다.
+#[test]
+fn test_arbitrary_bytes_round_trip() {
+    for seed in [0u8, 1, 7, 128, 255] {
+        let mut bytes = [seed; 32];
+        bytes[0] = 0;
+        bytes[31] = seed.wrapping_mul(3);
+        let key = Pubkey::new_from_array(bytes);
+        assert_eq!(Pubkey::from_str(&key.to_string()), Ok(key));
+    }
+}
+
+// base58 알파벳에 없는 문자나 잘못된 길이는 거절된다.
+#[test]
+fn test_invalid_input_is_rejected() {
+    assert_eq!(
+        Pubkey::from_str("0OIl"),
+        Err(ParsePubkeyError::InvalidCharacter {
+            character: '0',
+            index: 0
+        })
+    );
+    assert_eq!(Pubkey::from_str("abc"), Err(ParsePubkeyError::WrongSize));
+    assert_eq!(
+        Pubkey::from_str(&"z".repeat(45)),
+        Err(ParsePubkeyError::WrongSize)
+    );
+    assert_eq!(Pubkey::from_str(""), Err(ParsePubkeyError::WrongSize));
+}
+
+// new_unique는 호출마다 다른 키를 만들고, 나중에 만든 키가 더 크게 정렬된다.
+#[test]
+fn test_new_unique_is_distinct_and_ordered() {
+    let keys: Vec<Pubkey> = (0..100).map(|_| Pubkey::new_unique()).collect();
+    let distinct: HashSet<Pubkey> = keys.iter().copied().collect();
+    assert_eq!(distinct.len(), 100);
+    assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+// 이름 키는 짧은 이름을 그대로 담고, 32바이트를 넘는 이름도 패닉 없이 서로 다른 키로 만든다.
+#[test]
+fn test_from_name_is_deterministic_for_any_length() {
+    let alice = Pubkey::from_name("alice");
+    assert_eq!(&alice.as_bytes()[..5], b"alice");
+    assert!(alice.as_bytes()[5..].iter().all(|&b| b == 0));
+    assert_eq!(Pubkey::from_name("alice"), alice);
+
+    let long_a = "account-with-a-name-longer-than-32-bytes-a";
+    let long_b = "account-with-a-name-longer-than-32-bytes-b";
+    assert_eq!(Pubkey::from_name(long_a), Pubkey::from_name(long_a));
+    assert_ne!(Pubkey::from_name(long_a), Pubkey::from_name(long_b));
+    assert_ne!(Pubkey::from_name(long_a), Pubkey::from_name(&long_a[..32]));
+}
@@ -1,5 +1,7 @@
 use std::collections::BTreeMap;
 
+pub use pubkey::Pubkey;
+
 // 이 상태는 트랜잭션의 진행 상황을 쉽게 보여줘요. // This enum tells kids how a transaction is doing.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum TxStatus {
@@ -12,7 +14,7 @@ pub enum TxStatus {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PendingTx {
     pub id: String,              // Transaction hash
-    pub account: String,         // Sender's public key
+    pub account: Pubkey,         // Sender's public key
     pub fee_micro_lamports: u64, // Paid fee (1 lamports = 10^-6 SOL)
     pub payload_size: u32,       // Serialized transaction size(byte unit)
     pub status: TxStatus,        // Transaction status enum
@@ -61,11 +63,11 @@ pub fn filter_transactions<'a, F: MempoolFilter>(
 }
 
 // 계정별로 트랜잭션을 묶어 사전식 맵으로 돌려줘요. // Groups transactions per account, sorted by key.
-pub fn group_by_account(txs: &[PendingTx]) -> BTreeMap<String, Vec<PendingTx>> {
+pub fn group_by_account(txs: &[PendingTx]) -> BTreeMap<Pubkey, Vec<PendingTx>> {
     let mut grouped = BTreeMap::new();
     for tx in txs {
         grouped
-            .entry(tx.account.clone())
+            .entry(tx.account)
             .or_insert_with(Vec::new)
             .push(tx.clone());
     }
@@ -80,8 +82,8 @@ pub struct AccountStats {
 
 // 계정별로 총 수수료, 크기, 대기 수를 계산해요. // Calculates per account totals and pending count.
 pub fn compute_account_stats(
-    grouped: &BTreeMap<String, Vec<PendingTx>>,
-) -> BTreeMap<String, AccountStats> {
+    grouped: &BTreeMap<Pubkey, Vec<PendingTx>>,
+) -> BTreeMap<Pubkey, AccountStats> {
     let mut stats = BTreeMap::new();
     for (account, txs) in grouped {
         let mut total_fee = 0u64;
@@ -97,7 +99,7 @@ pub fn compute_account_stats(
             }
         }
         stats.insert(
-            account.clone(),
+            *account,
             AccountStats {
                 total_fee,
                 total_bytes,
@@ -5,7 +5,7 @@ use day5_mempool_pipeline::*;
 fn threshold_filter_checks_fee_payload_and_rejections() {
     let valid = PendingTx {
         id: "tx-valid".into(),
-        account: "alice".into(),
+        account: Pubkey::from_name("alice"),
         fee_micro_lamports: 500,
         payload_size: 200,
         status: TxStatus::Pending,
@@ -42,14 +42,14 @@ fn filter_transactions_returns_references_with_original_lifetime() {
     let txs = vec![
         PendingTx {
             id: "keep".into(),
-            account: "bob".into(),
+            account: Pubkey::from_name("bob"),
             fee_micro_lamports: 1_000,
             payload_size: 100,
             status: TxStatus::Pending,
         },
         PendingTx {
             id: "drop".into(),
-            account: "bob".into(),
+            account: Pubkey::from_name("bob"),
             fee_micro_lamports: 10,
             payload_size: 100,
             status: TxStatus::Rejected {
@@ -76,14 +76,14 @@ fn grouping_and_stats_summarize_accounts_correctly() {
     let txs = vec![
         PendingTx {
             id: "a1".into(),
-            account: "alice".into(),
+            account: Pubkey::from_name("alice"),
             fee_micro_lamports: 400,
             payload_size: 120,
             status: TxStatus::Pending,
         },
         PendingTx {
             id: "a2".into(),
-            account: "alice".into(),
+            account: Pubkey::from_name("alice"),
             fee_micro_lamports: 600,
             payload_size: 200,
             status: TxStatus::Simulated {
@@ -92,14 +92,14 @@ fn grouping_and_stats_summarize_accounts_correctly() {
         },
         PendingTx {
             id: "b1".into(),
-            account: "bob".into(),
+            account: Pubkey::from_name("bob"),
             fee_micro_lamports: 300,
             payload_size: 150,
             status: TxStatus::Pending,
         },
         PendingTx {
             id: "b2".into(),
-            account: "bob".into(),
+            account: Pubkey::from_name("bob"),
             fee_micro_lamports: 200,
             payload_size: 90,
             status: TxStatus::Rejected {
@@ -110,18 +110,21 @@ fn grouping_and_stats_summarize_accounts_correctly() {
 
     let grouped = group_by_account(&txs);
     let keys: Vec<_> = grouped.keys().cloned().collect();
-    assert_eq!(keys, vec!["alice".to_string(), "bob".to_string()]);
+    assert_eq!(
+        keys,
+        vec![Pubkey::from_name("alice"), Pubkey::from_name("bob")]
+    );
 
-    assert_eq!(grouped["alice"].len(), 2);
-    assert_eq!(grouped["bob"].len(), 2);
+    assert_eq!(grouped[&Pubkey::from_name("alice")].len(), 2);
+    assert_eq!(grouped[&Pubkey::from_name("bob")].len(), 2);
 
     let stats = compute_account_stats(&grouped);
-    let alice_stats = &stats["alice"];
+    let alice_stats = &stats[&Pubkey::from_name("alice")];
     assert_eq!(alice_stats.total_fee, 1_000);
     assert_eq!(alice_stats.total_bytes, 320);
     assert_eq!(alice_stats.pending, 1);
 
-    let bob_stats = &stats["bob"];
+    let bob_stats = &stats[&Pubkey::from_name("bob")];
     assert_eq!(bob_stats.total_fee, 500);
     assert_eq!(bob_stats.total_bytes, 240);
     assert_eq!(bob_stats.pending, 1);
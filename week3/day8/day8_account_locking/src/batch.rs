@@ -0,0 +1,128 @@
+// aim: 멤풀과 실행 사이에서 트랜잭션을 "서로 충돌하지 않는 묶음"으로 나눈다. (Sealevel 스타일)
+// 같은 배치 안의 트랜잭션은 동시에 실행해도 안전하고, 배치는 순서대로 실행한다.
+
+use std::collections::HashMap;
+
+use crate::{
+    AccountLockError, BlockConstraint, Pubkey, SlotExecutionState, TransactionMeta,
+    check_account_conflicts, lock_accounts, would_exceed_compute,
+};
+
+// 한 번에 병렬로 실행할 트랜잭션 묶음이다.
+// 배치마다 잠금 상태를 따로 두어 ExecutionQueue와 같은 충돌 규칙으로 검사한다.
+pub struct ParallelBatch {
+    pub transactions: Vec<TransactionMeta>,
+    state: SlotExecutionState,
+}
+
+impl ParallelBatch {
+    fn new() -> Self {
+        Self {
+            transactions: Vec::new(),
+            state: SlotExecutionState::default(),
+        }
+    }
+
+    // 배치에 담긴 트랜잭션 CU 합계
+    pub fn compute_units(&self) -> u32 {
+        self.state.consumed_compute_units
+    }
+
+    pub fn ids(&self) -> Vec<&str> {
+        self.transactions.iter().map(|tx| tx.id.as_str()).collect()
+    }
+}
+
+// 배치 빌더 결과이다.
+// critical_path_len은 충돌 의존성 사슬의 최장 길이로, CU 제한이 없을 때 필요한 최소 배치 수와 같다.
+// batch_count가 critical_path_len보다 크면 CU 제한 때문에 배치가 더 쪼개졌다는 뜻이다.
+pub struct BatchSchedule {
+    pub batches: Vec<ParallelBatch>,
+    pub critical_path_len: usize,
+}
+
+impl BatchSchedule {
+    pub fn batch_count(&self) -> usize {
+        self.batches.len()
+    }
+}
+
+// 입력 순서를 유지하면서 각 트랜잭션을 들어갈 수 있는 가장 이른 배치에 배정한다.
+// 1. 뒤에서부터 배치를 훑어 충돌하는 트랜잭션이 있는 마지막 배치를 찾는다. 그보다 앞에는 넣을 수 없다.
+//    (앞에 넣으면 충돌하는 두 트랜잭션의 원래 순서가 뒤집힌다.)
+// 2. 그 다음 배치부터 CU 여유가 있는 첫 배치에 넣고, 없으면 새 배치를 연다.
+// 단일 트랜잭션이 max_compute_units를 넘으면 어떤 배치에도 들어갈 수 없으므로 에러를 돌려준다.
+pub fn build_parallel_batches(
+    txs: &[TransactionMeta],
+    constraint: &BlockConstraint,
+) -> Result<BatchSchedule, AccountLockError> {
+    let mut batches: Vec<ParallelBatch> = Vec::new();
+    let mut depths = DependencyDepths::default();
+
+    for tx in txs {
+        if tx.compute_units > constraint.max_compute_units {
+            return Err(AccountLockError::ComputeLimitExceeded {
+                requested: tx.compute_units,
+                limit: constraint.max_compute_units,
+            });
+        }
+
+        let earliest = batches
+            .iter()
+            .rposition(|batch| check_account_conflicts(&batch.state, tx).is_some())
+            .map_or(0, |idx| idx + 1);
+
+        let slot = (earliest..batches.len())
+            .find(|&idx| !would_exceed_compute(&batches[idx].state, tx, constraint));
+        let idx = match slot {
+            Some(idx) => idx,
+            None => {
+                batches.push(ParallelBatch::new());
+                batches.len() - 1
+            }
+        };
+
+        lock_accounts(&mut batches[idx].state, tx);
+        batches[idx].transactions.push(tx.clone());
+        depths.record(tx);
+    }
+
+    Ok(BatchSchedule {
+        batches,
+        critical_path_len: depths.longest,
+    })
+}
+
+// 계정별로 "마지막 쓰기"와 "읽기 중 최대" 깊이를 기억해 의존성 사슬 길이를 계산한다.
+// - 쓰기는 이전의 쓰기와 읽기 모두에 의존한다.
+// - 읽기는 이전의 쓰기에만 의존한다.
+#[derive(Default)]
+struct DependencyDepths {
+    last_write: HashMap<Pubkey, usize>,
+    max_read: HashMap<Pubkey, usize>,
+    longest: usize,
+}
+
+impl DependencyDepths {
+    fn record(&mut self, tx: &TransactionMeta) {
+        let mut depends_on = 0;
+        for account in &tx.writable_accounts {
+            depends_on = depends_on
+                .max(self.last_write.get(account).copied().unwrap_or(0))
+                .max(self.max_read.get(account).copied().unwrap_or(0));
+        }
+        for account in &tx.readonly_accounts {
+            depends_on = depends_on.max(self.last_write.get(account).copied().unwrap_or(0));
+        }
+
+        let depth = depends_on + 1;
+        for account in &tx.writable_accounts {
+            self.last_write.insert(*account, depth);
+        }
+        for account in &tx.readonly_accounts {
+            let read = self.max_read.entry(*account).or_insert(0);
+            *read = (*read).max(depth);
+        }
+        self.longest = self.longest.max(depth);
+    }
+}
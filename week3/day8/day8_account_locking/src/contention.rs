@@ -0,0 +1,119 @@
+// aim: 어떤 계정이 병렬 실행을 직렬화시키는지 데이터로 확인한다.
+// 시간은 실제 시계 대신 ExecutionQueue 연산(enqueue/release) 한 번을 1틱으로 세는 논리 시계를 쓴다.
+
+use std::collections::HashMap;
+
+use crate::{Pubkey, TransactionMeta};
+
+// 계정 하나의 경합 통계
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountContention {
+    pub account: Pubkey,
+    pub conflicts: u64, // 이 계정 때문에 잠금을 얻지 못한 횟수
+    // conflicts 중 이 계정에 쓰려던 트랜잭션이 막힌 횟수. 쓰기가 매번 거절되어 write_locks가 0인 계정도 여기서 드러난다.
+    pub write_conflicts: u64,
+    pub write_locks: u64,
+    pub read_locks: u64,
+    pub write_hold_ticks: u64, // 쓰기 잠금을 보유한 논리 틱 합계
+    pub read_hold_ticks: u64,
+}
+
+// 슬롯 시뮬레이션이 끝난 뒤 덤프하기 위한 보고서
+#[derive(Debug)]
+pub struct ContentionReport {
+    pub ticks: u64,
+    pub total_conflicts: u64,
+    // 쓰기 충돌 횟수 → 쓰기 보유 틱 → 계정 키 순으로 정렬한 상위 N개 쓰기 계정
+    // 쓰기 잠금을 한 번도 얻지 못했어도 쓰기 충돌이 있었다면 포함한다.
+    pub hottest_writable: Vec<AccountContention>,
+    // 계정 키 순으로 정렬한 전체 통계
+    pub accounts: Vec<AccountContention>,
+}
+
+impl ContentionReport {
+    pub fn account(&self, account: &Pubkey) -> Option<&AccountContention> {
+        self.accounts.iter().find(|stats| stats.account == *account)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ContentionTracker {
+    tick: u64,
+    total_conflicts: u64,
+    // 트랜잭션 id → 잠금을 얻은 틱
+    locked_at: HashMap<String, u64>,
+    accounts: HashMap<Pubkey, AccountContention>,
+}
+
+impl ContentionTracker {
+    pub(crate) fn advance(&mut self) {
+        self.tick += 1;
+    }
+
+    pub(crate) fn record_conflict(&mut self, account: &Pubkey, tx: &TransactionMeta) {
+        self.total_conflicts += 1;
+        let writes = tx.writable_accounts.contains(account);
+        let stats = self.stats(account);
+        stats.conflicts += 1;
+        if writes {
+            stats.write_conflicts += 1;
+        }
+    }
+
+    pub(crate) fn record_lock(&mut self, tx: &TransactionMeta) {
+        self.locked_at.insert(tx.id.clone(), self.tick);
+        for account in &tx.writable_accounts {
+            self.stats(account).write_locks += 1;
+        }
+        for account in &tx.readonly_accounts {
+            self.stats(account).read_locks += 1;
+        }
+    }
+
+    pub(crate) fn record_unlock(&mut self, tx: &TransactionMeta) {
+        let Some(locked_at) = self.locked_at.remove(&tx.id) else {
+            return;
+        };
+        let held = self.tick - locked_at;
+        for account in &tx.writable_accounts {
+            self.stats(account).write_hold_ticks += held;
+        }
+        for account in &tx.readonly_accounts {
+            self.stats(account).read_hold_ticks += held;
+        }
+    }
+
+    pub(crate) fn report(&self, top_n: usize) -> ContentionReport {
+        let mut accounts: Vec<AccountContention> = self.accounts.values().cloned().collect();
+        accounts.sort_by_key(|stats| stats.account);
+
+        let mut hottest_writable: Vec<AccountContention> = accounts
+            .iter()
+            .filter(|stats| stats.write_locks > 0 || stats.write_conflicts > 0)
+            .cloned()
+            .collect();
+        hottest_writable.sort_by(|a, b| {
+            b.write_conflicts
+                .cmp(&a.write_conflicts)
+                .then(b.write_hold_ticks.cmp(&a.write_hold_ticks))
+                .then(a.account.cmp(&b.account))
+        });
+        hottest_writable.truncate(top_n);
+
+        ContentionReport {
+            ticks: self.tick,
+            total_conflicts: self.total_conflicts,
+            hottest_writable,
+            accounts,
+        }
+    }
+
+    fn stats(&mut self, account: &Pubkey) -> &mut AccountContention {
+        self.accounts
+            .entry(*account)
+            .or_insert_with(|| AccountContention {
+                account: *account,
+                ..Default::default()
+            })
+    }
+}
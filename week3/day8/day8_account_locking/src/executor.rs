@@ -0,0 +1,199 @@
+// aim: ExecutionQueue의 잠금을 실제 스레드 실행과 연결한다.
+// 워커는 잠금을 얻은 트랜잭션만 실행하고, 실행이 끝나면 release해서 막혀 있던 트랜잭션을 깨운다.
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{
+    AccountLockError, BlockConstraint, ExecutionQueue, Pubkey, TransactionMeta, check_sanitized,
+};
+
+// 워커 풀 실행 결과이다.
+// executed는 완료 순서대로 기록되며, 같은 계정을 두고 충돌하는 트랜잭션끼리는 입력 순서를 유지한다.
+pub struct ExecutionReport {
+    pub workers: usize,
+    pub executed: Vec<String>,
+    pub rejected: Vec<(String, AccountLockError)>,
+    pub elapsed: Duration,
+    // 동시에 잠금을 보유한 트랜잭션 수의 최대값
+    pub peak_in_flight: usize,
+}
+
+impl ExecutionReport {
+    // 초당 실행한 트랜잭션 수. 스레드 수별 처리량 비교에 쓴다.
+    pub fn throughput(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs == 0.0 {
+            return self.executed.len() as f64;
+        }
+        self.executed.len() as f64 / secs
+    }
+}
+
+// N개의 워커 스레드로 트랜잭션을 실행하는 로컬 실행 엔진이다.
+// BlockConstraint::max_compute_units는 "동시에 잠금을 보유할 수 있는 CU 예산"으로 쓰인다.
+pub struct ParallelExecutor {
+    constraint: BlockConstraint,
+    workers: usize,
+}
+
+// 워커들이 Mutex로 공유하는 스케줄링 상태
+struct Shared {
+    queue: ExecutionQueue,
+    backlog: VecDeque<TransactionMeta>,
+    executed: Vec<String>,
+    rejected: Vec<(String, AccountLockError)>,
+    peak_in_flight: usize,
+}
+
+impl ParallelExecutor {
+    // workers가 0이면 실행이 진행되지 않으므로 최소 1개로 맞춘다.
+    pub fn new(constraint: BlockConstraint, workers: usize) -> Self {
+        Self {
+            constraint,
+            workers: workers.max(1),
+        }
+    }
+
+    // 모든 트랜잭션을 실행할 때까지 블록한다. work는 트랜잭션마다 한 번씩, 잠금을 보유한 상태로 호출된다.
+    pub fn run<F>(&self, txs: Vec<TransactionMeta>, work: F) -> ExecutionReport
+    where
+        F: Fn(&TransactionMeta) + Sync,
+    {
+        let mut rejected = Vec::new();
+        let mut backlog = VecDeque::new();
+        for tx in txs {
+            // 구조가 잘못된 트랜잭션은 backlog에 넣지 않고 미리 거절한다.
+            if let Err(err) = check_sanitized(&tx) {
+                rejected.push((tx.id, err));
+            // 혼자서도 CU 예산을 넘는 트랜잭션은 영원히 잠금을 얻지 못하므로 미리 거절한다.
+            } else if tx.compute_units > self.constraint.max_compute_units {
+                let err = AccountLockError::ComputeLimitExceeded {
+                    requested: tx.compute_units,
+                    limit: self.constraint.max_compute_units,
+                };
+                rejected.push((tx.id, err));
+            } else {
+                backlog.push_back(tx);
+            }
+        }
+
+        let shared = Mutex::new(Shared {
+            queue: ExecutionQueue::new(self.constraint.clone()),
+            backlog,
+            executed: Vec::new(),
+            rejected,
+            peak_in_flight: 0,
+        });
+        let released = Condvar::new();
+
+        let started = Instant::now();
+        thread::scope(|scope| {
+            for _ in 0..self.workers {
+                scope.spawn(|| worker_loop(&shared, &released, &work));
+            }
+        });
+        let elapsed = started.elapsed();
+
+        let shared = shared
+            .into_inner()
+            .expect("워커가 패닉하면 실행 결과를 신뢰할 수 없다");
+        ExecutionReport {
+            workers: self.workers,
+            executed: shared.executed,
+            rejected: shared.rejected,
+            elapsed,
+            peak_in_flight: shared.peak_in_flight,
+        }
+    }
+}
+
+// 워커 한 개의 반복: 잠금 획득 → (Mutex 밖에서) 실행 → release 후 대기 중인 워커 깨우기
+fn worker_loop<F>(shared: &Mutex<Shared>, released: &Condvar, work: &F)
+where
+    F: Fn(&TransactionMeta) + Sync,
+{
+    loop {
+        let tx = {
+            let mut guard = shared.lock().expect("scheduler mutex poisoned");
+            loop {
+                if let Some(tx) = admit_next(&mut guard) {
+                    break tx;
+                }
+                if guard.backlog.is_empty() {
+                    // 더 이상 꺼낼 트랜잭션이 없다. 다른 워커도 깨워서 종료하게 한다.
+                    released.notify_all();
+                    return;
+                }
+                guard = released.wait(guard).expect("scheduler mutex poisoned");
+            }
+        };
+
+        work(&tx);
+
+        let mut guard = shared.lock().expect("scheduler mutex poisoned");
+        guard.queue.release(&tx.id);
+        guard.executed.push(tx.id);
+        released.notify_all();
+    }
+}
+
+// admit_next가 이번 훑기에서 건너뛴 트랜잭션들의 계정이다.
+// 충돌 여부만 필요하므로 CU는 세지 않는다. (backlog 전체 CU를 더하면 u32를 넘을 수 있다)
+#[derive(Default)]
+struct SkippedAccounts {
+    writable: HashSet<Pubkey>,
+    readonly: HashSet<Pubkey>,
+}
+
+impl SkippedAccounts {
+    // check_account_conflicts와 같은 규칙: 쓰기는 모든 사용과, 읽기는 쓰기와만 충돌한다.
+    fn conflicts(&self, tx: &TransactionMeta) -> bool {
+        tx.writable_accounts
+            .iter()
+            .any(|account| self.writable.contains(account) || self.readonly.contains(account))
+            || tx
+                .readonly_accounts
+                .iter()
+                .any(|account| self.writable.contains(account))
+    }
+
+    fn add(&mut self, tx: &TransactionMeta) {
+        self.writable.extend(&tx.writable_accounts);
+        self.readonly.extend(&tx.readonly_accounts);
+    }
+}
+
+// backlog를 앞에서부터 훑어 잠금을 얻을 수 있는 첫 트랜잭션을 꺼낸다.
+// 건너뛴 트랜잭션의 계정을 skipped에 모아, 뒤의 트랜잭션이 충돌하는 앞 트랜잭션을 추월하지 못하게 한다.
+// 슬롯 누적 한도에 걸린 트랜잭션은 기다려도 들어갈 수 없으므로 backlog에서 빼서 거절 목록으로 옮긴다.
+fn admit_next(shared: &mut Shared) -> Option<TransactionMeta> {
+    let mut skipped = SkippedAccounts::default();
+    let mut idx = 0;
+    while idx < shared.backlog.len() {
+        let candidate = &shared.backlog[idx];
+        if skipped.conflicts(candidate) {
+            skipped.add(candidate);
+            idx += 1;
+            continue;
+        }
+        match shared.queue.try_enqueue(candidate.clone()) {
+            Ok(()) => {
+                shared.peak_in_flight = shared.peak_in_flight.max(shared.queue.len());
+                return shared.backlog.remove(idx);
+            }
+            Err(err) if err.is_retryable() => {
+                skipped.add(candidate);
+                idx += 1;
+            }
+            Err(err) => {
+                if let Some(tx) = shared.backlog.remove(idx) {
+                    shared.rejected.push((tx.id, err));
+                }
+            }
+        }
+    }
+    None
+}
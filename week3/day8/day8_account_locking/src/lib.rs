@@ -1,6 +1,26 @@
 // aim: 동일한 계정을 동시에 두 트랜잭션이 사용하면 충돌. 따라서 대기열에 들어가기 전에 검증해야 함
 
-use std::collections::{HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+pub use pubkey::Pubkey;
+
+mod batch;
+mod contention;
+mod executor;
+mod lookup_table;
+mod sanitize;
+mod wait_queue;
+
+pub use batch::{BatchSchedule, ParallelBatch, build_parallel_batches};
+pub use contention::{AccountContention, ContentionReport};
+pub use executor::{ExecutionReport, ParallelExecutor};
+pub use lookup_table::{
+    AddressLookupError, AddressTableLookup, CompactTransaction, LookupTableStore,
+};
+pub use sanitize::{MAX_TX_ACCOUNT_LOCKS, SanitizeError, sanitize_transaction};
+
+use contention::ContentionTracker;
+use wait_queue::WaitQueue;
 
 // TransactionMeta는 읽기 가능한 계정과 쓰기 가능한 계정을 독립된 대기열로 관리해 충돌을 회피한다.
 // id는 이 트랜잭션의 컨텍스트를 구분하기 위한 식별자이며,
@@ -8,36 +28,117 @@ use std::collections::{HashSet, VecDeque};
 #[derive(Clone)]
 pub struct TransactionMeta {
     pub id: String,
-    pub writable_accounts: Vec<String>,
-    pub readonly_accounts: Vec<String>,
+    pub writable_accounts: Vec<Pubkey>,
+    pub readonly_accounts: Vec<Pubkey>,
     pub compute_units: u32,
 }
 
 // 슬롯에 이미 실행 중인 정보를 추적한다.
-// 읽기 가능한 계정과 쓰기 가능한 계정은 독립적이되 중복된 계정이 사용해선 안되기 때문에 HashSet으로 정의한다.
+// 쓰기 잠금은 한 트랜잭션만 가질 수 있으므로 HashSet으로 정의한다.
+// 읽기 잠금은 여러 트랜잭션이 공유하므로 계정별 보유자 수를 HashMap으로 센다.
+// 마지막 보유자가 해제해 카운트가 0이 되어야 계정이 맵에서 빠지고 쓰기 잠금을 받을 수 있다.
+#[derive(Default)]
 pub struct SlotExecutionState {
-    pub locked_writable: HashSet<String>,
-    pub locked_readonly: HashSet<String>,
+    pub locked_writable: HashSet<Pubkey>,
+    pub locked_readonly: HashMap<Pubkey, usize>,
     pub consumed_compute_units: u32, // 슬롯에서 소비된 총 CU
 }
 
+impl SlotExecutionState {
+    // 해당 계정에 읽기 잠금을 보유 중인 트랜잭션 수를 돌려준다. 잠금이 없으면 0이다.
+    pub fn readonly_holders(&self, account: &Pubkey) -> usize {
+        self.locked_readonly.get(account).copied().unwrap_or(0)
+    }
+}
+
+// 슬롯 하나에 누적되는 사용량이다. 잠금과 달리 release해도 줄지 않는다. (이미 블록에 들어간 트랜잭션이므로)
+// 대기열에 줄을 선 트랜잭션도 슬롯 자리를 예약한 것으로 보고 여기에 포함한다.
+#[derive(Default)]
+pub struct SlotUsage {
+    pub transactions: usize,
+    pub block_compute_units: u32,
+    // 쓰기 계정별 누적 CU. 한 핫 계정이 블록 전체를 차지하지 못하게 막는 데 쓴다.
+    pub account_compute_units: HashMap<Pubkey, u32>,
+}
+
+// max_compute_units는 동시에 잠금을 보유한 트랜잭션들의 CU 합 한도이고,
+// 나머지 한도는 슬롯 전체에 누적되는 값에 대한 한도이다.
+#[derive(Clone)]
 pub struct BlockConstraint {
     pub max_compute_units: u32,
     pub max_transactions: usize,
+    pub max_block_compute_units: u32,
+    pub max_account_compute_units: u32, // 쓰기 계정 하나가 슬롯에서 쓸 수 있는 CU 상한
 }
 
+#[derive(Debug, PartialEq, Eq)]
 pub enum AccountLockError {
-    Conflict { account: String }, // 트랜잭션이 계정을 동시에 사용하려 할 때 충돌한다.
-    ComputeLimitExceeded { requested: u32, limit: u32 }, // 한 슬롯에서 트랜잭션이 소비할 수 있는 CU가 초과되었다.
+    // 트랜잭션이 계정을 동시에 사용하려 할 때 충돌한다.
+    Conflict {
+        account: Pubkey,
+    },
+    // 한 슬롯에서 트랜잭션이 소비할 수 있는 CU가 초과되었다.
+    ComputeLimitExceeded {
+        requested: u32,
+        limit: u32,
+    },
+    // 슬롯에 들어간 트랜잭션 수가 max_transactions에 도달했다.
+    TransactionLimitReached {
+        limit: usize,
+    },
+    // 슬롯 누적 CU가 블록 한도를 넘는다.
+    BlockComputeLimitExceeded {
+        requested: u32,
+        limit: u32,
+    },
+    // 쓰기 계정 하나의 누적 CU가 상한을 넘는다.
+    AccountComputeLimitExceeded {
+        account: Pubkey,
+        requested: u32,
+        limit: u32,
+    },
+    // 같은 id의 트랜잭션이 이미 잠금을 보유하고 있거나 대기 중이다. id로 release하므로 중복을 허용하지 않는다.
+    DuplicateTransaction {
+        id: String,
+    },
+    // 구조가 잘못된 트랜잭션이다. 잠금 검사 전에 걸러내며, 다시 시도해도 통과할 수 없다.
+    InvalidTransaction {
+        reason: SanitizeError,
+    },
+}
+
+impl AccountLockError {
+    // 잠금이나 동시 CU가 풀리면 다시 시도할 수 있는 에러인지 알려준다.
+    // 슬롯 누적 한도에 걸린 트랜잭션은 이번 슬롯에서는 다시 시도해도 들어갈 수 없다.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            AccountLockError::Conflict { .. } | AccountLockError::ComputeLimitExceeded { .. }
+        )
+    }
+}
+
+// enqueue_or_wait의 결과. 바로 잠금을 얻었는지, 계정 대기열에 줄을 섰는지 알려준다.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Admission {
+    Admitted,
+    Waiting,
 }
 
 // 실행 대기 중인 트랜잭션을 대기열에 쌓기 위한 자료구조이다.
 pub struct ExecutionQueue {
     constraint: BlockConstraint,
     state: SlotExecutionState,
-    // Vec은 단방향 큐이다. 처리된 트랜잭션을 빼내게 되면 뒷 요소들을 전부 한 칸씩 옮겨야 하기 때문에 O(n) 시간 복잡도다.
-    // 따라서, 양방향 큐인 VecDeque를 사용해 처리된 트랜잭션을 빼내고 나서 다음 트랜잭션을 가리키는 포인터만 이동시켜 O(1) 시간 복잡도를 만족한다.
-    pending: VecDeque<TransactionMeta>,
+    // VecDeque는 앞/뒤 제거만 O(1)이고, release처럼 중간의 id를 찾아 빼면 position 스캔 + remove로 O(n)이 된다.
+    // 그래서 입장 순번(seq) → 트랜잭션을 BTreeMap에 두어 입장 순서 순회와 O(log n) 제거를 얻고,
+    // id → seq 인덱스를 HashMap에 두어 id 조회를 평균 O(1)로 만든다.
+    pending: BTreeMap<u64, TransactionMeta>,
+    pending_index: HashMap<String, u64>,
+    next_seq: u64,
+    // enqueue_or_wait로 들어왔지만 아직 잠금을 얻지 못한 트랜잭션들
+    waiting: WaitQueue,
+    usage: SlotUsage,
+    contention: ContentionTracker,
 }
 
 impl ExecutionQueue {
@@ -51,68 +152,340 @@ impl ExecutionQueue {
                 consumed_compute_units: 0,
             },
             pending: Default::default(),
+            pending_index: Default::default(),
+            next_seq: 0,
+            waiting: Default::default(),
+            usage: Default::default(),
+            contention: Default::default(),
         }
     }
 
+    // 충돌하면 기다리지 않고 Conflict로 거절한다. enqueue_or_wait로 줄 선 대기자와 충돌해도 거절하므로,
+    // 두 방식을 섞어 써도 try_enqueue가 먼저 기다리던 트랜잭션을 추월하지 않는다.
     pub fn try_enqueue(&mut self, tx: TransactionMeta) -> Result<(), AccountLockError> {
+        self.contention.advance();
+        check_sanitized(&tx)?;
+        self.check_duplicate(&tx)?;
+        self.check_slot_limits(&tx)?;
+
         if would_exceed_compute(&self.state, &tx, &self.constraint) {
-            // `requested` 는 현재 소비량 + 요청량 CU
+            // `requested` 는 현재 소비량 + 요청량 CU (u32를 넘으면 u32::MAX로 포화)
             return Err(AccountLockError::ComputeLimitExceeded {
-                requested: self.state.consumed_compute_units + tx.compute_units,
+                requested: self
+                    .state
+                    .consumed_compute_units
+                    .saturating_add(tx.compute_units),
                 limit: self.constraint.max_compute_units,
             });
         }
 
-        let account = check_account_conflicts(&self.state, &tx);
-        if account.is_some() {
-            return Err(AccountLockError::Conflict {
-                account: account.unwrap(),
+        if let Some(account) = self
+            .waiting
+            .blocking_account(&tx)
+            .or_else(|| check_account_conflicts(&self.state, &tx))
+        {
+            self.contention.record_conflict(&account, &tx);
+            return Err(AccountLockError::Conflict { account });
+        }
+
+        self.charge_slot(&tx);
+        self.lock(tx);
+        Ok(())
+    }
+
+    // 충돌하면 거절하는 대신 계정별 FIFO 대기열에 세워 두고, release가 잠금을 풀 때 자동으로 들여보낸다.
+    // 이미 기다리는 트랜잭션과 충돌하면 지금 당장 잠금이 비어 있어도 줄을 선다. (쓰기 기아 방지)
+    // 혼자서도 CU 제한을 넘는 트랜잭션은 영원히 들어갈 수 없으므로 ComputeLimitExceeded를 돌려준다.
+    // 슬롯 누적 한도는 줄을 서는 시점에 미리 예약하므로, 대기자는 나중에 한도 때문에 밀려나지 않는다.
+    pub fn enqueue_or_wait(&mut self, tx: TransactionMeta) -> Result<Admission, AccountLockError> {
+        self.contention.advance();
+        check_sanitized(&tx)?;
+        if tx.compute_units > self.constraint.max_compute_units {
+            return Err(AccountLockError::ComputeLimitExceeded {
+                requested: tx.compute_units,
+                limit: self.constraint.max_compute_units,
             });
         }
+        self.check_duplicate(&tx)?;
+        self.check_slot_limits(&tx)?;
+        self.charge_slot(&tx);
 
-        for account in tx.readonly_accounts.iter() {
-            self.state.locked_readonly.insert(account.clone());
+        let blocker = self
+            .waiting
+            .blocking_account(&tx)
+            .or_else(|| check_account_conflicts(&self.state, &tx));
+        if let Some(account) = &blocker {
+            self.contention.record_conflict(account, &tx);
         }
-        for account in tx.writable_accounts.iter() {
-            self.state.locked_writable.insert(account.clone());
+        if blocker.is_some() {
+            self.waiting.park(tx, false);
+            return Ok(Admission::Waiting);
         }
-        self.state.consumed_compute_units += tx.compute_units;
-        self.pending.push_back(tx);
-        Ok(())
+        if would_exceed_compute(&self.state, &tx, &self.constraint) {
+            self.waiting.park(tx, true);
+            return Ok(Admission::Waiting);
+        }
+
+        self.lock(tx);
+        Ok(Admission::Admitted)
+    }
+
+    // release 후 대기열에서 새로 잠금을 얻은 트랜잭션 id를 도착 순서대로 돌려준다.
+    pub fn release_and_admit(&mut self, tx_id: &str) -> Vec<String> {
+        self.contention.advance();
+        let freed = self.unlock(tx_id);
+        self.admit_waiting(&freed)
     }
 
     pub fn release(&mut self, tx_id: &str) {
-        if let Some(pos) = self.pending.iter().position(|tx| tx.id == tx_id) {
-            if let Some(tx) = self.pending.remove(pos) {
-                for account in &tx.writable_accounts {
-                    self.state.locked_writable.remove(account);
-                }
-                for account in &tx.readonly_accounts {
-                    self.state.locked_readonly.remove(account);
-                }
-                self.state.consumed_compute_units =
-                    self.state.consumed_compute_units.saturating_sub(tx.compute_units);
+        self.release_and_admit(tx_id);
+    }
+
+    // 잠금을 기다리는 트랜잭션 수
+    pub fn waiting_len(&self) -> usize {
+        self.waiting.len()
+    }
+
+    // 이번 슬롯에 누적된 사용량
+    pub fn usage(&self) -> &SlotUsage {
+        &self.usage
+    }
+
+    // 지금까지의 계정 경합 통계. 아직 release되지 않은 잠금의 보유 시간은 포함하지 않는다.
+    pub fn contention_report(&self, top_n: usize) -> ContentionReport {
+        self.contention.report(top_n)
+    }
+
+    // 잠금을 보유 중인 트랜잭션을 id로 찾는다.
+    pub fn get(&self, tx_id: &str) -> Option<&TransactionMeta> {
+        let seq = self.pending_index.get(tx_id)?;
+        self.pending.get(seq)
+    }
+
+    // 잠금을 보유 중인 트랜잭션을 입장(잠금 획득) 순서대로 순회한다.
+    pub fn iter(&self) -> impl Iterator<Item = &TransactionMeta> {
+        self.pending.values()
+    }
+
+    // 슬롯이 끝났을 때 호출한다. (release_all)
+    // 모든 잠금과 동시 CU, 대기열, 슬롯 누적 사용량을 비우고,
+    // 끝나지 못한 트랜잭션을 (잠금 보유 중인 것 → 대기 중인 것) 순서로 돌려줘 다음 슬롯에 다시 넣을 수 있게 한다.
+    // 경합 통계는 슬롯을 넘어 누적되므로 지우지 않는다.
+    pub fn reset_slot(&mut self) -> Vec<TransactionMeta> {
+        self.contention.advance();
+        let mut unfinished = Vec::with_capacity(self.pending.len() + self.waiting.len());
+        for tx in std::mem::take(&mut self.pending).into_values() {
+            self.contention.record_unlock(&tx);
+            unfinished.push(tx);
+        }
+        unfinished.extend(self.waiting.drain());
+        self.pending_index.clear();
+        self.state = SlotExecutionState::default();
+        self.usage = SlotUsage::default();
+        unfinished
+    }
+
+    fn check_duplicate(&self, tx: &TransactionMeta) -> Result<(), AccountLockError> {
+        if self.pending_index.contains_key(&tx.id) || self.waiting.contains_id(&tx.id) {
+            return Err(AccountLockError::DuplicateTransaction { id: tx.id.clone() });
+        }
+        Ok(())
+    }
+
+    // 슬롯 누적 한도 검사: 트랜잭션 수 → 블록 CU → 쓰기 계정별 CU 순서로 확인한다.
+    // 각 한도마다 에러 변형이 달라 어떤 한도에 걸렸는지 통계에서 구분할 수 있다.
+    fn check_slot_limits(&self, tx: &TransactionMeta) -> Result<(), AccountLockError> {
+        if self.usage.transactions >= self.constraint.max_transactions {
+            return Err(AccountLockError::TransactionLimitReached {
+                limit: self.constraint.max_transactions,
+            });
+        }
+
+        // would_exceed_compute와 같은 규칙: 합이 u32를 넘으면 어떤 한도보다도 크므로 초과로 본다.
+        // 포화 덧셈으로 비교하면 한도가 u32::MAX일 때 넘친 합이 한도와 같아져 통과해 버린다.
+        let used = self.usage.block_compute_units;
+        if used
+            .checked_add(tx.compute_units)
+            .is_none_or(|total| total > self.constraint.max_block_compute_units)
+        {
+            return Err(AccountLockError::BlockComputeLimitExceeded {
+                requested: used.saturating_add(tx.compute_units),
+                limit: self.constraint.max_block_compute_units,
+            });
+        }
+
+        for account in &tx.writable_accounts {
+            let used = self
+                .usage
+                .account_compute_units
+                .get(account)
+                .copied()
+                .unwrap_or(0);
+            if used
+                .checked_add(tx.compute_units)
+                .is_none_or(|total| total > self.constraint.max_account_compute_units)
+            {
+                return Err(AccountLockError::AccountComputeLimitExceeded {
+                    account: *account,
+                    requested: used.saturating_add(tx.compute_units),
+                    limit: self.constraint.max_account_compute_units,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    // check_slot_limits를 통과한 트랜잭션만 누적하므로, 합이 u32를 넘는 경우는 이미 거절되었다.
+    fn charge_slot(&mut self, tx: &TransactionMeta) {
+        self.usage.transactions += 1;
+        self.usage.block_compute_units = self
+            .usage
+            .block_compute_units
+            .checked_add(tx.compute_units)
+            .expect("check_slot_limits가 블록 CU 오버플로를 먼저 거절한다");
+        for account in &tx.writable_accounts {
+            let used = self
+                .usage
+                .account_compute_units
+                .entry(*account)
+                .or_insert(0);
+            *used = used
+                .checked_add(tx.compute_units)
+                .expect("check_slot_limits가 계정 CU 오버플로를 먼저 거절한다");
+        }
+    }
+
+    // freed 계정으로 풀렸을 수 있는 대기자만 도착 순서대로 훑어, 앞선 충돌 대기자가 없고 잠금이 가능한 것만 들여보낸다.
+    // CU 때문에 막힌 트랜잭션도 자기 계정의 줄은 계속 지키므로, 충돌하는 뒷사람이 추월하지 못한다.
+    fn admit_waiting(&mut self, freed: &[Pubkey]) -> Vec<String> {
+        let mut admitted = Vec::new();
+        for seq in self.waiting.candidates(freed) {
+            let Some(tx) = self.waiting.get(seq) else {
+                continue;
+            };
+            if self.waiting.blocked_by_earlier(seq, tx)
+                || check_account_conflicts(&self.state, tx).is_some()
+            {
+                self.waiting.set_compute_blocked(seq, false);
+                continue;
+            }
+            if would_exceed_compute(&self.state, tx, &self.constraint) {
+                self.waiting.set_compute_blocked(seq, true);
+                continue;
+            }
+            if let Some(tx) = self.waiting.remove(seq) {
+                admitted.push(tx.id.clone());
+                self.lock(tx);
             }
         }
+        admitted
+    }
+
+    fn lock(&mut self, tx: TransactionMeta) {
+        lock_accounts(&mut self.state, &tx);
+        self.contention.record_lock(&tx);
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.pending_index.insert(tx.id.clone(), seq);
+        self.pending.insert(seq, tx);
+    }
+
+    // 잠금을 풀고, 풀린 트랜잭션의 계정을 돌려준다. 모르는 id면 빈 목록이다.
+    fn unlock(&mut self, tx_id: &str) -> Vec<Pubkey> {
+        let Some(seq) = self.pending_index.remove(tx_id) else {
+            return Vec::new();
+        };
+        let Some(tx) = self.pending.remove(&seq) else {
+            return Vec::new();
+        };
+        self.contention.record_unlock(&tx);
+        for account in &tx.writable_accounts {
+            self.state.locked_writable.remove(account);
+        }
+        // 읽기 잠금은 보유자 한 명분만 줄이고, 마지막 보유자일 때만 계정을 맵에서 제거한다.
+        for account in &tx.readonly_accounts {
+            release_readonly(&mut self.state, account);
+        }
+        self.state.consumed_compute_units = self
+            .state
+            .consumed_compute_units
+            .saturating_sub(tx.compute_units);
+        tx.writable_accounts
+            .into_iter()
+            .chain(tx.readonly_accounts)
+            .collect()
+    }
+
+    // 현재 잠금 상태를 읽기 전용으로 노출한다. 테스트와 통계 수집에서 사용한다.
+    pub fn state(&self) -> &SlotExecutionState {
+        &self.state
+    }
+
+    // 잠금을 보유한 채 대기열에 남아 있는 트랜잭션 수
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+// 잠금 검사 전에 트랜잭션 구조를 검증한다. 중복 계정이나 쓰기/읽기 겹침이 있으면 잠금 카운트가 어긋나므로
+// 모든 입장 경로(try_enqueue, enqueue_or_wait, ParallelExecutor)가 이 검사를 먼저 거친다.
+pub(crate) fn check_sanitized(tx: &TransactionMeta) -> Result<(), AccountLockError> {
+    tx.sanitize()
+        .map_err(|reason| AccountLockError::InvalidTransaction { reason })
+}
+
+// 충돌 검사를 통과한 트랜잭션의 계정을 잠그고 CU를 누적한다.
+// 대기열과 병렬 배치 빌더가 같은 잠금 규칙을 쓰도록 한 곳에 모아 둔다.
+pub(crate) fn lock_accounts(state: &mut SlotExecutionState, tx: &TransactionMeta) {
+    for account in tx.readonly_accounts.iter() {
+        *state.locked_readonly.entry(*account).or_insert(0) += 1;
+    }
+    for account in tx.writable_accounts.iter() {
+        state.locked_writable.insert(*account);
+    }
+    state.consumed_compute_units = state
+        .consumed_compute_units
+        .saturating_add(tx.compute_units);
+}
+
+// 읽기 잠금 카운트를 하나 줄이고, 0이 되면 계정을 맵에서 지워 쓰기 잠금이 가능하게 한다.
+fn release_readonly(state: &mut SlotExecutionState, account: &Pubkey) {
+    if let Some(count) = state.locked_readonly.get_mut(account) {
+        *count -= 1;
+        if *count == 0 {
+            state.locked_readonly.remove(account);
+        }
     }
 }
 
 // 현재 사용량에 새 트랜잭션의 컴퓨트 유닛을 더했을 때 제한 초과시 true
-fn would_exceed_compute(
+// 합이 u32를 넘으면 어떤 한도보다도 크므로 초과로 본다.
+pub(crate) fn would_exceed_compute(
     state: &SlotExecutionState,
     tx: &TransactionMeta,
     constraint: &BlockConstraint,
 ) -> bool {
-    state.consumed_compute_units + tx.compute_units > constraint.max_compute_units
+    state
+        .consumed_compute_units
+        .checked_add(tx.compute_units)
+        .is_none_or(|total| total > constraint.max_compute_units)
 }
 
 // 충돌이 있으면 해당 계정 이름을 반환하고, 없으면 None
-fn check_account_conflicts(state: &SlotExecutionState, tx: &TransactionMeta) -> Option<String> {
+pub(crate) fn check_account_conflicts(
+    state: &SlotExecutionState,
+    tx: &TransactionMeta,
+) -> Option<Pubkey> {
     // 1. 쓰기 계정 충돌
     // - 새 트랜잭션의 writable_accounts가 이미 잠긴 locked_writable 또는 locked_readonly와 겹치면 충돌이다.
+    // - locked_readonly에는 보유자가 1명 이상인 계정만 남아 있으므로 contains_key로 충분하다.
     for account in &tx.writable_accounts {
-        if state.locked_writable.contains(account) || state.locked_readonly.contains(account) {
-            return Some(account.clone());
+        if state.locked_writable.contains(account) || state.locked_readonly.contains_key(account) {
+            return Some(*account);
         }
     }
 
@@ -120,7 +493,7 @@ fn check_account_conflicts(state: &SlotExecutionState, tx: &TransactionMeta) ->
     // - 새 트랜잭션의 readonly_accounts가 기존의 locked_writable과 겹치면 역시 충돌이다.
     for account in &tx.readonly_accounts {
         if state.locked_writable.contains(account) {
-            return Some(account.clone());
+            return Some(*account);
         }
     }
 
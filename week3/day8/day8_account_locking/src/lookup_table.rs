@@ -0,0 +1,120 @@
+// aim: v0 트랜잭션이 주소 조회 테이블(ALT)로 참조한 계정을 풀어서 TransactionMeta로 만든다.
+// ALT를 쓰면 트랜잭션에는 32바이트 키 대신 1바이트 인덱스만 실리므로, 잠금 전에 반드시 전체 키로 펼쳐야 한다.
+
+use std::collections::HashMap;
+
+use crate::{Pubkey, SanitizeError, TransactionMeta};
+
+// 테이블 하나에서 어떤 인덱스를 쓰기/읽기로 가져올지 적은 목록 (Solana의 MessageAddressTableLookup)
+#[derive(Clone, Debug)]
+pub struct AddressTableLookup {
+    pub table: Pubkey,
+    pub writable_indexes: Vec<u8>,
+    pub readonly_indexes: Vec<u8>,
+}
+
+// 정적 키와 테이블 인덱스 목록으로만 계정을 표현한 압축 트랜잭션
+#[derive(Clone, Debug)]
+pub struct CompactTransaction {
+    pub id: String,
+    pub static_writable: Vec<Pubkey>,
+    pub static_readonly: Vec<Pubkey>,
+    pub address_table_lookups: Vec<AddressTableLookup>,
+    pub compute_units: u32,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum AddressLookupError {
+    // 참조한 테이블이 저장소에 없다.
+    LookupTableNotFound {
+        table: Pubkey,
+    },
+    // 인덱스가 테이블 길이를 벗어난다.
+    InvalidLookupIndex {
+        table: Pubkey,
+        index: u8,
+        len: usize,
+    },
+    // 펼친 결과가 검증을 통과하지 못했다. 테이블을 거치면 정적 키와 같은 계정이 섞여 들어갈 수 있다.
+    InvalidTransaction {
+        reason: SanitizeError,
+    },
+}
+
+// 테이블 주소 → 주소 목록. 실제 체인에서는 ALT 계정 데이터에 해당한다.
+#[derive(Default)]
+pub struct LookupTableStore {
+    tables: HashMap<Pubkey, Vec<Pubkey>>,
+}
+
+impl LookupTableStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 같은 주소로 다시 넣으면 테이블 내용을 교체한다.
+    pub fn insert(&mut self, table: Pubkey, addresses: Vec<Pubkey>) {
+        self.tables.insert(table, addresses);
+    }
+
+    // ALT는 뒤에 주소를 덧붙이는 방식으로만 늘어나므로 extend만 제공한다.
+    pub fn extend(&mut self, table: Pubkey, addresses: &[Pubkey]) {
+        self.tables
+            .entry(table)
+            .or_default()
+            .extend_from_slice(addresses);
+    }
+
+    pub fn get(&self, table: &Pubkey) -> Option<&[Pubkey]> {
+        self.tables.get(table).map(Vec::as_slice)
+    }
+
+    // Solana 계정 순서와 같이 펼친다.
+    // writable = 정적 쓰기 키 → 각 테이블의 쓰기 인덱스(테이블 순서대로)
+    // readonly = 정적 읽기 키 → 각 테이블의 읽기 인덱스(테이블 순서대로)
+    // 하나라도 풀 수 없으면 부분 결과 없이 에러를 돌려준다.
+    // 펼친 뒤에 sanitize를 거치므로, 여기서 나온 TransactionMeta는 바로 잠금 검사에 넣을 수 있다.
+    pub fn resolve(&self, tx: &CompactTransaction) -> Result<TransactionMeta, AddressLookupError> {
+        let mut writable_accounts = tx.static_writable.clone();
+        let mut readonly_accounts = tx.static_readonly.clone();
+
+        for lookup in &tx.address_table_lookups {
+            let addresses =
+                self.get(&lookup.table)
+                    .ok_or(AddressLookupError::LookupTableNotFound {
+                        table: lookup.table,
+                    })?;
+            for &index in &lookup.writable_indexes {
+                writable_accounts.push(lookup_address(lookup.table, addresses, index)?);
+            }
+            for &index in &lookup.readonly_indexes {
+                readonly_accounts.push(lookup_address(lookup.table, addresses, index)?);
+            }
+        }
+
+        let meta = TransactionMeta {
+            id: tx.id.clone(),
+            writable_accounts,
+            readonly_accounts,
+            compute_units: tx.compute_units,
+        };
+        meta.sanitize()
+            .map_err(|reason| AddressLookupError::InvalidTransaction { reason })?;
+        Ok(meta)
+    }
+}
+
+fn lookup_address(
+    table: Pubkey,
+    addresses: &[Pubkey],
+    index: u8,
+) -> Result<Pubkey, AddressLookupError> {
+    addresses
+        .get(index as usize)
+        .copied()
+        .ok_or(AddressLookupError::InvalidLookupIndex {
+            table,
+            index,
+            len: addresses.len(),
+        })
+}
@@ -0,0 +1,70 @@
+// aim: 잠금 단계에 들어가기 전에 모양이 잘못된 TransactionMeta를 걸러낸다.
+// 잘못된 입력(같은 계정을 두 번 적거나 쓰기/읽기에 동시에 적는 등)은 잠금 상태를 헷갈리게 만든다.
+
+use std::collections::HashSet;
+
+use crate::{Pubkey, TransactionMeta};
+
+// Solana 런타임이 한 트랜잭션에 허용하는 계정 잠금 수 기본값
+pub const MAX_TX_ACCOUNT_LOCKS: usize = 64;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SanitizeError {
+    // id가 비어 있으면 release로 잠금을 풀 방법이 없다.
+    EmptyId,
+    // CU가 0인 트랜잭션은 실행 비용을 계산할 수 없다.
+    ZeroComputeUnits,
+    // 쓰기 + 읽기 계정 수의 합이 한도를 넘는다.
+    TooManyAccountKeys { count: usize, limit: usize },
+    // 같은 목록 안에 같은 계정이 두 번 이상 들어 있다.
+    DuplicateAccount { account: Pubkey },
+    // 한 계정이 쓰기와 읽기 목록에 동시에 들어 있다.
+    WritableAndReadonly { account: Pubkey },
+}
+
+// 검사는 싼 것부터 순서대로 하고, 처음 발견한 문제 하나만 돌려준다.
+pub fn sanitize_transaction(
+    tx: &TransactionMeta,
+    max_account_keys: usize,
+) -> Result<(), SanitizeError> {
+    if tx.id.is_empty() {
+        return Err(SanitizeError::EmptyId);
+    }
+    if tx.compute_units == 0 {
+        return Err(SanitizeError::ZeroComputeUnits);
+    }
+
+    let count = tx.writable_accounts.len() + tx.readonly_accounts.len();
+    if count > max_account_keys {
+        return Err(SanitizeError::TooManyAccountKeys {
+            count,
+            limit: max_account_keys,
+        });
+    }
+
+    let mut writable = HashSet::new();
+    for account in &tx.writable_accounts {
+        if !writable.insert(account) {
+            return Err(SanitizeError::DuplicateAccount { account: *account });
+        }
+    }
+
+    let mut readonly = HashSet::new();
+    for account in &tx.readonly_accounts {
+        if writable.contains(account) {
+            return Err(SanitizeError::WritableAndReadonly { account: *account });
+        }
+        if !readonly.insert(account) {
+            return Err(SanitizeError::DuplicateAccount { account: *account });
+        }
+    }
+
+    Ok(())
+}
+
+impl TransactionMeta {
+    // Solana 기본 계정 한도(MAX_TX_ACCOUNT_LOCKS)로 검사한다.
+    pub fn sanitize(&self) -> Result<(), SanitizeError> {
+        sanitize_transaction(self, MAX_TX_ACCOUNT_LOCKS)
+    }
+}
@@ -0,0 +1,144 @@
+// aim: 충돌로 바로 잠금을 얻지 못한 트랜잭션을 계정별 FIFO 대기열에 세워 둔다.
+// 같은 계정을 두고 충돌하는 트랜잭션끼리는 도착 순서대로만 잠금을 얻으므로,
+// 읽기가 계속 들어와도 먼저 기다리던 쓰기가 굶지 않는다.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use crate::{Pubkey, TransactionMeta};
+
+// 계정 대기열의 한 칸. 같은 트랜잭션이 여러 계정 대기열에 동시에 설 수 있어 seq로 묶는다.
+struct Waiter {
+    seq: u64,
+    writes: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct WaitQueue {
+    next_seq: u64,
+    // 도착 순서(seq) → 대기 중인 트랜잭션. BTreeMap이라 seq 오름차순 순회가 곧 FIFO 순회다.
+    parked: BTreeMap<u64, TransactionMeta>,
+    // 계정 → 그 계정을 기다리는 트랜잭션들의 FIFO
+    per_account: HashMap<Pubkey, VecDeque<Waiter>>,
+    ids: HashSet<String>,
+    // 계정 충돌 없이 CU 예산만 모자라 기다리는 트랜잭션. 계정과 무관하게 아무 release로나 풀릴 수 있어 따로 모은다.
+    compute_blocked: BTreeSet<u64>,
+}
+
+impl WaitQueue {
+    pub(crate) fn len(&self) -> usize {
+        self.parked.len()
+    }
+
+    // compute_blocked는 충돌 없이 CU 때문에만 줄을 서는지 여부다.
+    pub(crate) fn park(&mut self, tx: TransactionMeta, compute_blocked: bool) {
+        let seq = self.next_seq;
+        if compute_blocked {
+            self.compute_blocked.insert(seq);
+        }
+        self.next_seq += 1;
+        for account in &tx.writable_accounts {
+            self.per_account
+                .entry(*account)
+                .or_default()
+                .push_back(Waiter { seq, writes: true });
+        }
+        for account in &tx.readonly_accounts {
+            self.per_account
+                .entry(*account)
+                .or_default()
+                .push_back(Waiter { seq, writes: false });
+        }
+        self.ids.insert(tx.id.clone());
+        self.parked.insert(seq, tx);
+    }
+
+    pub(crate) fn contains_id(&self, tx_id: &str) -> bool {
+        self.ids.contains(tx_id)
+    }
+
+    // 대기 중인 트랜잭션을 도착 순서대로 모두 꺼내고 대기열을 비운다.
+    pub(crate) fn drain(&mut self) -> Vec<TransactionMeta> {
+        self.per_account.clear();
+        self.ids.clear();
+        self.compute_blocked.clear();
+        std::mem::take(&mut self.parked).into_values().collect()
+    }
+
+    // 새로 도착한 트랜잭션이 이미 기다리는 트랜잭션과 충돌하면 줄을 서야 한다. 충돌한 계정을 돌려준다.
+    pub(crate) fn blocking_account(&self, tx: &TransactionMeta) -> Option<Pubkey> {
+        self.conflicting_waiter_account(tx, u64::MAX).copied()
+    }
+
+    // freed 계정의 release로 들어갈 수 있게 됐을지도 모르는 대기자 seq를 도착 순서대로 돌려준다.
+    // 그 계정 대기열에 선 트랜잭션과 CU만 모자란 트랜잭션이 후보이고, 나머지는 여전히 풀리지 않은 잠금이나
+    // 앞선 대기자에 막혀 있으므로 훑지 않는다. 순회 중 제거할 수 있도록 복사본을 만든다.
+    pub(crate) fn candidates(&self, freed: &[Pubkey]) -> BTreeSet<u64> {
+        let mut candidates = self.compute_blocked.clone();
+        for account in freed {
+            candidates.extend(
+                self.per_account
+                    .get(account)
+                    .into_iter()
+                    .flatten()
+                    .map(|waiter| waiter.seq),
+            );
+        }
+        candidates
+    }
+
+    // 대기자를 다시 검사한 결과를 기록한다. CU만 모자라면 다음 release에서도 후보가 된다.
+    pub(crate) fn set_compute_blocked(&mut self, seq: u64, compute_blocked: bool) {
+        if compute_blocked {
+            self.compute_blocked.insert(seq);
+        } else {
+            self.compute_blocked.remove(&seq);
+        }
+    }
+
+    pub(crate) fn get(&self, seq: u64) -> Option<&TransactionMeta> {
+        self.parked.get(&seq)
+    }
+
+    // seq보다 먼저 줄 선 트랜잭션 중 충돌하는 것이 남아 있으면 true
+    pub(crate) fn blocked_by_earlier(&self, seq: u64, tx: &TransactionMeta) -> bool {
+        self.conflicting_waiter_account(tx, seq).is_some()
+    }
+
+    pub(crate) fn remove(&mut self, seq: u64) -> Option<TransactionMeta> {
+        let tx = self.parked.remove(&seq)?;
+        self.ids.remove(&tx.id);
+        self.compute_blocked.remove(&seq);
+        for account in tx.writable_accounts.iter().chain(&tx.readonly_accounts) {
+            if let Some(waiters) = self.per_account.get_mut(account) {
+                waiters.retain(|waiter| waiter.seq != seq);
+                if waiters.is_empty() {
+                    self.per_account.remove(account);
+                }
+            }
+        }
+        Some(tx)
+    }
+
+    // 쓰기는 앞선 모든 대기자와, 읽기는 앞선 쓰기 대기자와만 충돌한다. (check_account_conflicts와 같은 규칙)
+    fn conflicting_waiter_account<'a>(
+        &self,
+        tx: &'a TransactionMeta,
+        before_seq: u64,
+    ) -> Option<&'a Pubkey> {
+        let earlier = |account: &Pubkey| {
+            self.per_account
+                .get(account)
+                .into_iter()
+                .flatten()
+                .take_while(move |waiter| waiter.seq < before_seq)
+        };
+        tx.writable_accounts
+            .iter()
+            .find(|account| earlier(account).next().is_some())
+            .or_else(|| {
+                tx.readonly_accounts
+                    .iter()
+                    .find(|account| earlier(account).any(|waiter| waiter.writes))
+            })
+    }
+}
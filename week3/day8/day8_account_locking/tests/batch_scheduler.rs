@@ -0,0 +1,120 @@
+// 이 테스트 모음은 병렬 배치 빌더가 충돌 없는 배치를 만들고 충돌 순서를 지키는지 확인한다.
+use day8_account_locking::{
+    AccountLockError, BlockConstraint, Pubkey, TransactionMeta, build_parallel_batches,
+};
+
+fn tx(id: &str, writable: &[&str], readonly: &[&str], compute_units: u32) -> TransactionMeta {
+    TransactionMeta {
+        id: id.to_string(),
+        writable_accounts: writable.iter().map(|a| Pubkey::from_name(a)).collect(),
+        readonly_accounts: readonly.iter().map(|a| Pubkey::from_name(a)).collect(),
+        compute_units,
+    }
+}
+
+fn constraint(max_compute_units: u32) -> BlockConstraint {
+    BlockConstraint {
+        max_compute_units,
+        max_transactions: 64,
+        max_block_compute_units: u32::MAX,
+        max_account_compute_units: u32::MAX,
+    }
+}
+
+// 서로 다른 계정을 쓰는 트랜잭션과 공유 읽기는 한 배치에 모인다.
+#[test]
+fn test_independent_transactions_share_one_batch() {
+    let txs = vec![
+        tx("a", &["alice"], &["oracle"], 10),
+        tx("b", &["bob"], &["oracle"], 10),
+        tx("c", &["carol"], &[], 10),
+    ];
+    let schedule = build_parallel_batches(&txs, &constraint(1_000))
+        .ok()
+        .unwrap();
+
+    assert_eq!(schedule.batch_count(), 1);
+    assert_eq!(schedule.critical_path_len, 1);
+    assert_eq!(schedule.batches[0].ids(), vec!["a", "b", "c"]);
+    assert_eq!(schedule.batches[0].compute_units(), 30);
+}
+
+// 충돌하는 트랜잭션은 원래 순서대로 뒤 배치에 배정되고, 충돌 없는 것은 앞 배치로 당겨진다.
+#[test]
+fn test_conflicts_keep_original_order() {
+    let txs = vec![
+        tx("w1", &["pool"], &[], 10),
+        tx("r1", &[], &["pool"], 10),
+        tx("x", &["other"], &[], 10),
+        tx("w2", &["pool"], &[], 10),
+        tx("r2", &[], &["pool"], 10),
+    ];
+    let schedule = build_parallel_batches(&txs, &constraint(1_000))
+        .ok()
+        .unwrap();
+
+    let ids: Vec<Vec<&str>> = schedule.batches.iter().map(|b| b.ids()).collect();
+    assert_eq!(
+        ids,
+        vec![vec!["w1", "x"], vec!["r1"], vec!["w2"], vec!["r2"]]
+    );
+    assert_eq!(schedule.critical_path_len, 4);
+}
+
+// 읽기 뒤에 오는 독립 트랜잭션이 충돌 사슬 앞쪽 배치의 빈 자리를 채운다.
+#[test]
+fn test_later_transaction_fills_earlier_batch() {
+    let txs = vec![
+        tx("w1", &["pool"], &[], 10),
+        tx("w2", &["pool"], &[], 10),
+        tx("r", &[], &["oracle"], 10),
+        tx("w3", &["oracle"], &[], 10),
+    ];
+    let schedule = build_parallel_batches(&txs, &constraint(1_000))
+        .ok()
+        .unwrap();
+
+    let ids: Vec<Vec<&str>> = schedule.batches.iter().map(|b| b.ids()).collect();
+    assert_eq!(ids, vec![vec!["w1", "r"], vec!["w2", "w3"]]);
+    assert_eq!(schedule.critical_path_len, 2);
+}
+
+// CU 제한 때문에 배치가 쪼개지면 batch_count가 critical_path_len보다 커진다.
+#[test]
+fn test_compute_limit_splits_batches() {
+    let txs = vec![
+        tx("a", &["alice"], &[], 60),
+        tx("b", &["bob"], &[], 60),
+        tx("c", &["carol"], &[], 30),
+    ];
+    let schedule = build_parallel_batches(&txs, &constraint(100)).expect("batches");
+
+    let ids: Vec<Vec<&str>> = schedule.batches.iter().map(|b| b.ids()).collect();
+    assert_eq!(ids, vec![vec!["a", "c"], vec!["b"]]);
+    assert_eq!(schedule.batch_count(), 2);
+    assert_eq!(schedule.critical_path_len, 1);
+    assert!(schedule.batches.iter().all(|b| b.compute_units() <= 100));
+}
+
+// 혼자서도 제한을 넘는 트랜잭션은 배치에 넣을 수 없다.
+#[test]
+fn test_oversized_transaction_is_rejected() {
+    let txs = vec![tx("huge", &["alice"], &[], 500)];
+    let result = build_parallel_batches(&txs, &constraint(100));
+
+    assert_eq!(
+        result.err(),
+        Some(AccountLockError::ComputeLimitExceeded {
+            requested: 500,
+            limit: 100
+        })
+    );
+}
+
+// 빈 입력은 빈 스케줄을 만든다.
+#[test]
+fn test_empty_input() {
+    let schedule = build_parallel_batches(&[], &constraint(100)).expect("batches");
+    assert_eq!(schedule.batch_count(), 0);
+    assert_eq!(schedule.critical_path_len, 0);
+}
@@ -0,0 +1,172 @@
+// 이 테스트 모음은 ExecutionQueue가 계정별 충돌 횟수와 잠금 보유 틱을 정확히 집계하는지 확인한다.
+use day8_account_locking::{BlockConstraint, ExecutionQueue, Pubkey, TransactionMeta};
+
+fn tx(id: &str, writable: &[&str], readonly: &[&str], compute_units: u32) -> TransactionMeta {
+    TransactionMeta {
+        id: id.to_string(),
+        writable_accounts: writable.iter().map(|a| Pubkey::from_name(a)).collect(),
+        readonly_accounts: readonly.iter().map(|a| Pubkey::from_name(a)).collect(),
+        compute_units,
+    }
+}
+
+fn queue() -> ExecutionQueue {
+    ExecutionQueue::new(BlockConstraint {
+        max_compute_units: 10_000,
+        max_transactions: 1_024,
+        max_block_compute_units: u32::MAX,
+        max_account_compute_units: u32::MAX,
+    })
+}
+
+// Conflict로 거절될 때마다 원인 계정의 충돌 횟수가 오른다.
+#[test]
+fn test_conflicts_are_counted_per_account() {
+    let mut queue = queue();
+    queue.try_enqueue(tx("w1", &["amm"], &[], 10)).ok();
+    queue.try_enqueue(tx("w2", &["amm"], &[], 10)).ok();
+    queue.try_enqueue(tx("r1", &[], &["amm"], 10)).ok();
+    queue.try_enqueue(tx("a", &["alice"], &[], 10)).ok();
+
+    let report = queue.contention_report(5);
+    assert_eq!(report.total_conflicts, 2);
+    assert_eq!(
+        report.account(&Pubkey::from_name("amm")).unwrap().conflicts,
+        2
+    );
+    assert_eq!(
+        report
+            .account(&Pubkey::from_name("alice"))
+            .unwrap()
+            .conflicts,
+        0
+    );
+}
+
+// 보유 틱은 잠금을 얻은 연산부터 release 연산까지의 논리 틱 차이이다.
+#[test]
+fn test_hold_ticks_are_measured_in_logical_ticks() {
+    let mut queue = queue();
+    queue.try_enqueue(tx("w", &["amm"], &["oracle"], 10)).ok(); // tick 1
+    queue.try_enqueue(tx("a", &["alice"], &[], 10)).ok(); // tick 2
+    queue.release("a"); // tick 3
+    queue.release("w"); // tick 4
+
+    let report = queue.contention_report(5);
+    assert_eq!(report.ticks, 4);
+    let amm = report.account(&Pubkey::from_name("amm")).unwrap();
+    assert_eq!(amm.write_locks, 1);
+    assert_eq!(amm.write_hold_ticks, 3);
+    assert_eq!(
+        report
+            .account(&Pubkey::from_name("oracle"))
+            .unwrap()
+            .read_hold_ticks,
+        3
+    );
+    assert_eq!(
+        report
+            .account(&Pubkey::from_name("alice"))
+            .unwrap()
+            .write_hold_ticks,
+        1
+    );
+}
+
+// 대기열에 줄을 선 것도 충돌로 세고, 대기 후 잠금을 얻은 시점부터 보유 틱을 잰다.
+#[test]
+fn test_waiting_counts_as_conflict() {
+    let mut queue = queue();
+    queue.enqueue_or_wait(tx("w1", &["amm"], &[], 10)).ok(); // tick 1
+    queue.enqueue_or_wait(tx("w2", &["amm"], &[], 10)).ok(); // tick 2, 대기
+    queue.enqueue_or_wait(tx("r", &[], &["amm"], 10)).ok(); // tick 3, w2 뒤에 대기
+    queue.release("w1"); // tick 4, w2 입장
+    queue.release("w2"); // tick 5, r 입장
+    queue.release("r"); // tick 6
+
+    let amm = queue
+        .contention_report(1)
+        .account(&Pubkey::from_name("amm"))
+        .cloned()
+        .unwrap();
+    assert_eq!(amm.conflicts, 2);
+    assert_eq!(amm.write_locks, 2);
+    assert_eq!(amm.write_hold_ticks, 3 + 1);
+    assert_eq!(amm.read_hold_ticks, 1);
+}
+
+// 가장 뜨거운 쓰기 계정은 쓰기 충돌 횟수가 많은 순으로 top-N만 남는다.
+#[test]
+fn test_top_n_hottest_writable_accounts() {
+    let mut queue = queue();
+    queue.try_enqueue(tx("amm-0", &["amm"], &[], 10)).ok();
+    queue.try_enqueue(tx("vault-0", &["vault"], &[], 10)).ok();
+    queue
+        .try_enqueue(tx("alice-0", &["alice"], &["oracle"], 10))
+        .ok();
+    for i in 1..=3 {
+        queue
+            .try_enqueue(tx(&format!("amm-{i}"), &["amm"], &[], 10))
+            .ok();
+    }
+    queue.try_enqueue(tx("vault-1", &["vault"], &[], 10)).ok();
+
+    let report = queue.contention_report(2);
+    let hottest: Vec<(Pubkey, u64)> = report
+        .hottest_writable
+        .iter()
+        .map(|stats| (stats.account, stats.conflicts))
+        .collect();
+    assert_eq!(
+        hottest,
+        vec![
+            (Pubkey::from_name("amm"), 3),
+            (Pubkey::from_name("vault"), 1)
+        ]
+    );
+    // 읽기로만 쓰인 계정은 쓰기 순위에 들어가지 않는다.
+    assert!(report.account(&Pubkey::from_name("oracle")).is_some());
+    assert!(
+        queue
+            .contention_report(10)
+            .hottest_writable
+            .iter()
+            .all(|stats| stats.account != Pubkey::from_name("oracle"))
+    );
+}
+
+// 읽기 보유자 때문에 쓰기가 매번 거절된 계정도 쓰기 충돌 횟수로 순위에 오른다.
+#[test]
+fn test_always_rejected_writers_are_ranked_by_write_conflicts() {
+    let mut queue = queue();
+    queue.try_enqueue(tx("r", &[], &["pool"], 10)).ok();
+    for i in 0..3 {
+        queue
+            .try_enqueue(tx(&format!("pool-w{i}"), &["pool"], &[], 10))
+            .ok();
+    }
+    queue.try_enqueue(tx("amm-0", &["amm"], &[], 10)).ok();
+    queue.try_enqueue(tx("amm-1", &["amm"], &[], 10)).ok();
+    // 쓰기 잠금에 막힌 읽기는 충돌이지만 쓰기 충돌은 아니다.
+    queue.try_enqueue(tx("amm-r", &[], &["amm"], 10)).ok();
+
+    let report = queue.contention_report(5);
+    let pool = report.account(&Pubkey::from_name("pool")).unwrap();
+    assert_eq!(pool.write_locks, 0);
+    assert_eq!(pool.write_conflicts, 3);
+    let amm = report.account(&Pubkey::from_name("amm")).unwrap();
+    assert_eq!((amm.conflicts, amm.write_conflicts), (2, 1));
+
+    let hottest: Vec<(Pubkey, u64)> = report
+        .hottest_writable
+        .iter()
+        .map(|stats| (stats.account, stats.write_conflicts))
+        .collect();
+    assert_eq!(
+        hottest,
+        vec![
+            (Pubkey::from_name("pool"), 3),
+            (Pubkey::from_name("amm"), 1)
+        ]
+    );
+}
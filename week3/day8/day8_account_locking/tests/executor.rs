@@ -0,0 +1,229 @@
+// 이 테스트 모음은 워커 풀이 잠금을 지키며 모든 트랜잭션을 실행하는지 확인한다.
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use day8_account_locking::{
+    AccountLockError, BlockConstraint, ParallelExecutor, Pubkey, SanitizeError, TransactionMeta,
+};
+
+fn tx(id: &str, writable: &[&str], readonly: &[&str], compute_units: u32) -> TransactionMeta {
+    TransactionMeta {
+        id: id.to_string(),
+        writable_accounts: writable.iter().map(|a| Pubkey::from_name(a)).collect(),
+        readonly_accounts: readonly.iter().map(|a| Pubkey::from_name(a)).collect(),
+        compute_units,
+    }
+}
+
+fn constraint(max_compute_units: u32) -> BlockConstraint {
+    BlockConstraint {
+        max_compute_units,
+        max_transactions: 1_024,
+        max_block_compute_units: u32::MAX,
+        max_account_compute_units: u32::MAX,
+    }
+}
+
+// 워크로드 안에서 실제로 보유 중인 잠금을 따로 기록해, 충돌하는 두 트랜잭션이 동시에 실행되면 실패시킨다.
+#[derive(Default)]
+struct LockAuditor {
+    // 계정 → (쓰기 보유자 수, 읽기 보유자 수)
+    active: Mutex<HashMap<Pubkey, (usize, usize)>>,
+    violations: Mutex<Vec<String>>,
+}
+
+impl LockAuditor {
+    fn enter(&self, tx: &TransactionMeta) {
+        let mut active = self.active.lock().unwrap();
+        for account in &tx.writable_accounts {
+            let entry = active.entry(*account).or_default();
+            if entry.0 > 0 || entry.1 > 0 {
+                self.violations.lock().unwrap().push(tx.id.clone());
+            }
+            entry.0 += 1;
+        }
+        for account in &tx.readonly_accounts {
+            let entry = active.entry(*account).or_default();
+            if entry.0 > 0 {
+                self.violations.lock().unwrap().push(tx.id.clone());
+            }
+            entry.1 += 1;
+        }
+    }
+
+    fn exit(&self, tx: &TransactionMeta) {
+        let mut active = self.active.lock().unwrap();
+        for account in &tx.writable_accounts {
+            active.get_mut(account).unwrap().0 -= 1;
+        }
+        for account in &tx.readonly_accounts {
+            active.get_mut(account).unwrap().1 -= 1;
+        }
+    }
+}
+
+// 모든 트랜잭션이 정확히 한 번씩 실행되고, 충돌하는 트랜잭션은 입력 순서대로 끝난다.
+#[test]
+fn test_executes_every_transaction_in_conflict_order() {
+    let txs = vec![
+        tx("w1", &["pool"], &[], 10),
+        tx("r1", &[], &["pool"], 10),
+        tx("a", &["alice"], &[], 10),
+        tx("w2", &["pool"], &[], 10),
+    ];
+    let executor = ParallelExecutor::new(constraint(1_000), 4);
+    let report = executor.run(txs, |_| thread::sleep(Duration::from_millis(2)));
+
+    assert_eq!(report.executed.len(), 4);
+    assert!(report.rejected.is_empty());
+    let pos = |id: &str| report.executed.iter().position(|e| e == id).unwrap();
+    assert!(pos("w1") < pos("r1"));
+    assert!(pos("r1") < pos("w2"));
+}
+
+// CU 예산보다 큰 트랜잭션은 실행하지 않고 거절 목록에 남긴다.
+#[test]
+fn test_oversized_transaction_is_rejected() {
+    let txs = vec![
+        tx("huge", &["alice"], &[], 500),
+        tx("ok", &["bob"], &[], 10),
+    ];
+    let executor = ParallelExecutor::new(constraint(100), 2);
+    let report = executor.run(txs, |_| {});
+
+    assert_eq!(report.executed, vec!["ok".to_string()]);
+    assert_eq!(
+        report.rejected,
+        vec![(
+            "huge".to_string(),
+            AccountLockError::ComputeLimitExceeded {
+                requested: 500,
+                limit: 100
+            }
+        )]
+    );
+}
+
+// 구조가 잘못된 트랜잭션은 실행하지 않고 InvalidTransaction으로 거절 목록에 남긴다.
+#[test]
+fn test_malformed_transaction_is_rejected() {
+    let txs = vec![
+        tx("bad", &["pool"], &["pool"], 10),
+        tx("ok", &["pool"], &[], 10),
+    ];
+    let executor = ParallelExecutor::new(constraint(100), 2);
+    let report = executor.run(txs, |_| {});
+
+    assert_eq!(report.executed, vec!["ok".to_string()]);
+    assert_eq!(
+        report.rejected,
+        vec![(
+            "bad".to_string(),
+            AccountLockError::InvalidTransaction {
+                reason: SanitizeError::WritableAndReadonly {
+                    account: Pubkey::from_name("pool")
+                }
+            }
+        )]
+    );
+}
+
+// 핫 계정을 섞은 대량 워크로드에서 어떤 두 워커도 충돌하는 잠금을 동시에 보유하지 않는다.
+#[test]
+fn test_stress_no_conflicting_locks_held_concurrently() {
+    let accounts = ["amm", "oracle", "alice", "bob", "carol", "dave"];
+    let txs: Vec<TransactionMeta> = (0..400)
+        .map(|i| {
+            let w = accounts[i % accounts.len()];
+            let r = accounts[(i * 7 + 3) % accounts.len()];
+            let readonly: &[&str] = if r == w { &[] } else { &[r] };
+            tx(&format!("tx-{i}"), &[w], readonly, 10)
+        })
+        .collect();
+
+    let auditor = LockAuditor::default();
+    let executor = ParallelExecutor::new(constraint(10_000), 8);
+    let report = executor.run(txs, |tx| {
+        auditor.enter(tx);
+        thread::yield_now();
+        auditor.exit(tx);
+    });
+
+    assert_eq!(report.executed.len(), 400);
+    assert!(auditor.violations.lock().unwrap().is_empty());
+    assert!(report.peak_in_flight <= 8);
+}
+
+// 독립적인 트랜잭션은 워커 수를 늘리면 더 빨리 끝난다.
+#[test]
+fn test_more_workers_increase_throughput() {
+    let txs: Vec<TransactionMeta> = (0..32)
+        .map(|i| tx(&format!("tx-{i}"), &[&format!("acc-{i}")], &[], 10))
+        .collect();
+    let workload = |_: &TransactionMeta| thread::sleep(Duration::from_millis(5));
+
+    let single = ParallelExecutor::new(constraint(10_000), 1).run(txs.clone(), workload);
+    let pooled = ParallelExecutor::new(constraint(10_000), 4).run(txs, workload);
+
+    assert_eq!(single.executed.len(), 32);
+    assert_eq!(pooled.executed.len(), 32);
+    assert!(pooled.throughput() > single.throughput());
+}
+
+// 슬롯 누적 한도에 걸린 트랜잭션은 기다리지 않고 거절 목록으로 간다.
+#[test]
+fn test_slot_limit_rejections_do_not_block_executor() {
+    let txs = vec![
+        tx("a1", &["amm"], &[], 60),
+        tx("a2", &["amm"], &[], 60),
+        tx("b", &["bob"], &[], 60),
+    ];
+    let executor = ParallelExecutor::new(
+        BlockConstraint {
+            max_compute_units: 1_000,
+            max_transactions: 64,
+            max_block_compute_units: u32::MAX,
+            max_account_compute_units: 100,
+        },
+        2,
+    );
+    let report = executor.run(txs, |_| {});
+
+    assert_eq!(report.executed.len(), 2);
+    assert_eq!(
+        report.rejected,
+        vec![(
+            "a2".to_string(),
+            AccountLockError::AccountComputeLimitExceeded {
+                account: Pubkey::from_name("amm"),
+                requested: 120,
+                limit: 100
+            }
+        )]
+    );
+}
+
+// 한 핫 계정에 쓰는 긴 backlog도 건너뛴 트랜잭션의 CU를 더하지 않으므로 오버플로 없이 입력 순서대로 모두 실행된다.
+// 3,000 × 1.4M CU는 u32 범위 안이므로 블록·계정 한도(u32::MAX)에 걸리지 않는다.
+#[test]
+fn test_hot_account_backlog_does_not_overflow() {
+    let txs: Vec<TransactionMeta> = (0..3_000)
+        .map(|i| tx(&format!("tx-{i}"), &["hot"], &[], 1_400_000))
+        .collect();
+    let executor = ParallelExecutor::new(
+        BlockConstraint {
+            max_compute_units: 48_000_000,
+            max_transactions: 3_000,
+            max_block_compute_units: u32::MAX,
+            max_account_compute_units: u32::MAX,
+        },
+        4,
+    );
+    let report = executor.run(txs, |_| {});
+
+    assert!(report.rejected.is_empty());
+    let expected: Vec<String> = (0..3_000).map(|i| format!("tx-{i}")).collect();
+    assert_eq!(report.executed, expected);
+}
@@ -1 +1,396 @@
+// 이 테스트 모음은 ExecutionQueue가 계정 잠금과 컴퓨트 제한을 올바르게 추적하는지 확인한다.
+use day8_account_locking::{
+    AccountLockError, BlockConstraint, ExecutionQueue, Pubkey, TransactionMeta,
+};
 
+fn tx(id: &str, writable: &[&str], readonly: &[&str], compute_units: u32) -> TransactionMeta {
+    TransactionMeta {
+        id: id.to_string(),
+        writable_accounts: writable.iter().map(|a| Pubkey::from_name(a)).collect(),
+        readonly_accounts: readonly.iter().map(|a| Pubkey::from_name(a)).collect(),
+        compute_units,
+    }
+}
+
+fn queue(max_compute_units: u32) -> ExecutionQueue {
+    ExecutionQueue::new(BlockConstraint {
+        max_compute_units,
+        max_transactions: 64,
+        max_block_compute_units: u32::MAX,
+        max_account_compute_units: u32::MAX,
+    })
+}
+
+// 충돌 없는 두 트랜잭션이 들어가고, release 후 상태가 초기화되는지 확인한다.
+#[test]
+fn test_enqueue_and_release_without_conflict() {
+    let mut queue = queue(1_000);
+    assert!(
+        queue
+            .try_enqueue(tx("t1", &["alice"], &["oracle"], 300))
+            .is_ok()
+    );
+    assert!(
+        queue
+            .try_enqueue(tx("t2", &["bob"], &["oracle"], 200))
+            .is_ok()
+    );
+
+    assert_eq!(queue.state().consumed_compute_units, 500);
+    assert_eq!(
+        queue.state().readonly_holders(&Pubkey::from_name("oracle")),
+        2
+    );
+    assert_eq!(queue.len(), 2);
+
+    queue.release("t1");
+    queue.release("t2");
+    assert_eq!(queue.state().consumed_compute_units, 0);
+    assert!(queue.state().locked_writable.is_empty());
+    assert!(queue.state().locked_readonly.is_empty());
+    assert!(queue.is_empty());
+}
+
+// 같은 계정을 쓰려는 트랜잭션은 Conflict로 거부된다.
+#[test]
+fn test_write_conflict_is_rejected() {
+    let mut queue = queue(1_000);
+    queue.try_enqueue(tx("t1", &["alice"], &[], 100)).ok();
+
+    let result = queue.try_enqueue(tx("t2", &["alice"], &[], 100));
+    assert_eq!(
+        result,
+        Err(AccountLockError::Conflict {
+            account: Pubkey::from_name("alice")
+        })
+    );
+
+    queue.release("t1");
+    assert!(queue.try_enqueue(tx("t2", &["alice"], &[], 100)).is_ok());
+}
+
+// 컴퓨트 제한을 넘으면 ComputeLimitExceeded로 거부되고 상태는 변하지 않는다.
+#[test]
+fn test_compute_limit_exceeded() {
+    let mut queue = queue(500);
+    queue.try_enqueue(tx("t1", &["alice"], &[], 400)).ok();
+
+    let result = queue.try_enqueue(tx("t2", &["bob"], &[], 200));
+    assert_eq!(
+        result,
+        Err(AccountLockError::ComputeLimitExceeded {
+            requested: 600,
+            limit: 500
+        })
+    );
+    assert_eq!(queue.state().consumed_compute_units, 400);
+
+    queue.release("t1");
+    assert_eq!(queue.state().consumed_compute_units, 0);
+}
+
+// 첫 번째 읽기 보유자가 해제돼도 두 번째 보유자의 잠금은 유지되어 쓰기를 막아야 한다.
+#[test]
+fn test_shared_read_lock_survives_first_release() {
+    let mut queue = queue(1_000);
+    queue.try_enqueue(tx("r1", &[], &["pool"], 10)).ok();
+    queue.try_enqueue(tx("r2", &[], &["pool"], 10)).ok();
+
+    queue.release("r1");
+    assert_eq!(
+        queue.state().readonly_holders(&Pubkey::from_name("pool")),
+        1
+    );
+    assert_eq!(
+        queue.try_enqueue(tx("w1", &["pool"], &[], 10)),
+        Err(AccountLockError::Conflict {
+            account: Pubkey::from_name("pool")
+        })
+    );
+
+    queue.release("r2");
+    assert_eq!(
+        queue.state().readonly_holders(&Pubkey::from_name("pool")),
+        0
+    );
+    assert!(queue.try_enqueue(tx("w1", &["pool"], &[], 10)).is_ok());
+}
+
+// 여러 읽기 보유자의 enqueue/release를 교차시켜도 카운트가 정확히 유지되는지 확인한다.
+#[test]
+fn test_interleaved_readers_and_writer() {
+    let mut queue = queue(1_000);
+    queue.try_enqueue(tx("r1", &[], &["pool"], 10)).ok();
+    queue
+        .try_enqueue(tx("r2", &[], &["pool", "oracle"], 10))
+        .ok();
+    queue.release("r1");
+    queue.try_enqueue(tx("r3", &[], &["pool"], 10)).ok();
+    assert_eq!(
+        queue.state().readonly_holders(&Pubkey::from_name("pool")),
+        2
+    );
+    assert_eq!(
+        queue.state().readonly_holders(&Pubkey::from_name("oracle")),
+        1
+    );
+
+    queue.release("r2");
+    assert_eq!(
+        queue.state().readonly_holders(&Pubkey::from_name("pool")),
+        1
+    );
+    assert_eq!(
+        queue.state().readonly_holders(&Pubkey::from_name("oracle")),
+        0
+    );
+    assert!(
+        queue
+            .try_enqueue(tx("w-oracle", &["oracle"], &[], 10))
+            .is_ok()
+    );
+    assert!(queue.try_enqueue(tx("w-pool", &["pool"], &[], 10)).is_err());
+
+    queue.release("r3");
+    assert!(queue.try_enqueue(tx("w-pool", &["pool"], &[], 10)).is_ok());
+    // 쓰기 잠금이 잡힌 동안에는 새 읽기도 막혀야 한다.
+    assert!(queue.try_enqueue(tx("r4", &[], &["pool"], 10)).is_err());
+}
+
+// 존재하지 않는 id나 이미 해제된 id를 release해도 카운트가 음수로 내려가지 않는다.
+#[test]
+fn test_release_unknown_or_twice_is_noop() {
+    let mut queue = queue(1_000);
+    queue.try_enqueue(tx("r1", &[], &["pool"], 10)).ok();
+    queue.try_enqueue(tx("r2", &[], &["pool"], 10)).ok();
+
+    queue.release("r1");
+    queue.release("r1");
+    queue.release("missing");
+    assert_eq!(
+        queue.state().readonly_holders(&Pubkey::from_name("pool")),
+        1
+    );
+    assert_eq!(queue.state().consumed_compute_units, 10);
+}
+
+fn limited_queue(
+    max_transactions: usize,
+    max_block_compute_units: u32,
+    max_account_compute_units: u32,
+) -> ExecutionQueue {
+    ExecutionQueue::new(BlockConstraint {
+        max_compute_units: 1_000,
+        max_transactions,
+        max_block_compute_units,
+        max_account_compute_units,
+    })
+}
+
+// 소비량 + 요청량이 u32를 넘으면 패닉하지 않고 초과로 거절한다. 한도가 u32::MAX여도 넘친 합은 통과하지 못한다.
+#[test]
+fn test_compute_overflow_is_rejected_instead_of_panicking() {
+    let mut queue = limited_queue(64, u32::MAX, u32::MAX);
+    queue.try_enqueue(tx("t1", &["alice"], &[], 10)).ok();
+    assert_eq!(
+        queue.try_enqueue(tx("t2", &["bob"], &[], u32::MAX - 5)),
+        Err(AccountLockError::BlockComputeLimitExceeded {
+            requested: u32::MAX,
+            limit: u32::MAX
+        })
+    );
+
+    let mut queue = ExecutionQueue::new(BlockConstraint {
+        max_compute_units: u32::MAX,
+        max_transactions: 64,
+        max_block_compute_units: u32::MAX,
+        max_account_compute_units: u32::MAX,
+    });
+    queue
+        .try_enqueue(tx("t1", &["amm"], &[], u32::MAX - 5))
+        .unwrap();
+    queue.release("t1");
+    assert_eq!(
+        queue.try_enqueue(tx("t2", &["vault"], &[], 10)),
+        Err(AccountLockError::BlockComputeLimitExceeded {
+            requested: u32::MAX,
+            limit: u32::MAX
+        })
+    );
+    assert_eq!(queue.usage().block_compute_units, u32::MAX - 5);
+
+    // 계정 누적 CU는 블록 누적 CU를 넘지 않으므로, 계정 합이 넘치면 블록 검사에서 먼저 걸린다.
+    // 블록 한도에 여유가 있을 때는 같은 계정의 누적 CU가 계정 한도를 넘는 순간 거절된다.
+    let mut queue = ExecutionQueue::new(BlockConstraint {
+        max_compute_units: u32::MAX,
+        max_transactions: 64,
+        max_block_compute_units: u32::MAX,
+        max_account_compute_units: u32::MAX - 10,
+    });
+    queue
+        .try_enqueue(tx("t1", &["amm"], &[], u32::MAX - 15))
+        .unwrap();
+    queue.release("t1");
+    assert_eq!(
+        queue.try_enqueue(tx("t2", &["amm"], &[], 10)),
+        Err(AccountLockError::AccountComputeLimitExceeded {
+            account: Pubkey::from_name("amm"),
+            requested: u32::MAX - 5,
+            limit: u32::MAX - 10
+        })
+    );
+}
+
+// 슬롯 트랜잭션 수 한도는 release 후에도 풀리지 않는다.
+#[test]
+fn test_transaction_count_limit() {
+    let mut queue = limited_queue(2, u32::MAX, u32::MAX);
+    queue.try_enqueue(tx("t1", &["alice"], &[], 10)).ok();
+    queue.try_enqueue(tx("t2", &["bob"], &[], 10)).ok();
+    queue.release("t1");
+
+    assert_eq!(
+        queue.try_enqueue(tx("t3", &["carol"], &[], 10)),
+        Err(AccountLockError::TransactionLimitReached { limit: 2 })
+    );
+    assert_eq!(queue.usage().transactions, 2);
+}
+
+// 블록 누적 CU는 release로 줄지 않으므로 동시 CU 한도와 별개로 막힌다.
+#[test]
+fn test_block_compute_limit() {
+    let mut queue = limited_queue(64, 250, u32::MAX);
+    queue.try_enqueue(tx("t1", &["alice"], &[], 200)).ok();
+    queue.release("t1");
+
+    assert_eq!(
+        queue.try_enqueue(tx("t2", &["bob"], &[], 100)),
+        Err(AccountLockError::BlockComputeLimitExceeded {
+            requested: 300,
+            limit: 250
+        })
+    );
+    assert!(queue.try_enqueue(tx("t3", &["bob"], &[], 50)).is_ok());
+    assert_eq!(queue.usage().block_compute_units, 250);
+}
+
+// 핫 계정 하나가 쓸 수 있는 누적 CU를 넘으면 거절되지만, 읽기 전용 사용은 세지 않는다.
+#[test]
+fn test_account_compute_limit() {
+    let mut queue = limited_queue(64, u32::MAX, 150);
+    queue.try_enqueue(tx("t1", &["amm"], &[], 100)).ok();
+    queue.release("t1");
+
+    assert_eq!(
+        queue.try_enqueue(tx("t2", &["amm"], &[], 100)),
+        Err(AccountLockError::AccountComputeLimitExceeded {
+            account: Pubkey::from_name("amm"),
+            requested: 200,
+            limit: 150
+        })
+    );
+    assert!(
+        queue
+            .try_enqueue(tx("t3", &["alice"], &["amm"], 100))
+            .is_ok()
+    );
+    assert_eq!(
+        queue
+            .usage()
+            .account_compute_units
+            .get(&Pubkey::from_name("amm")),
+        Some(&100)
+    );
+}
+
+// 거절된 트랜잭션은 슬롯 사용량에 반영되지 않는다.
+#[test]
+fn test_rejection_does_not_charge_usage() {
+    let mut queue = limited_queue(64, u32::MAX, u32::MAX);
+    queue.try_enqueue(tx("t1", &["alice"], &[], 10)).ok();
+    assert!(queue.try_enqueue(tx("t2", &["alice"], &[], 10)).is_err());
+
+    assert_eq!(queue.usage().transactions, 1);
+    assert_eq!(queue.usage().block_compute_units, 10);
+}
+
+// id로 조회하고, 입장 순서대로 순회할 수 있다. 중간 항목을 release해도 나머지 순서는 유지된다.
+#[test]
+fn test_lookup_and_admission_order_iteration() {
+    let mut queue = queue(10_000);
+    for id in ["t1", "t2", "t3", "t4"] {
+        queue.try_enqueue(tx(id, &[id], &[], 10)).ok();
+    }
+    queue.release("t2");
+
+    assert_eq!(queue.get("t3").map(|tx| tx.compute_units), Some(10));
+    assert!(queue.get("t2").is_none());
+    let ids: Vec<&str> = queue.iter().map(|tx| tx.id.as_str()).collect();
+    assert_eq!(ids, vec!["t1", "t3", "t4"]);
+}
+
+// 같은 id가 이미 잠금을 보유 중이면 DuplicateTransaction으로 거절된다.
+#[test]
+fn test_duplicate_id_is_rejected() {
+    let mut queue = queue(1_000);
+    queue.try_enqueue(tx("t1", &["alice"], &[], 10)).ok();
+
+    assert_eq!(
+        queue.try_enqueue(tx("t1", &["bob"], &[], 10)),
+        Err(AccountLockError::DuplicateTransaction {
+            id: "t1".to_string()
+        })
+    );
+    queue.release("t1");
+    assert!(queue.try_enqueue(tx("t1", &["bob"], &[], 10)).is_ok());
+}
+
+// 슬롯 리셋은 잠금, 동시 CU, 슬롯 누적 사용량을 모두 비우고 끝나지 못한 트랜잭션을 돌려준다.
+#[test]
+fn test_reset_slot_clears_locks_and_usage() {
+    let mut queue = limited_queue(2, u32::MAX, u32::MAX);
+    queue
+        .enqueue_or_wait(tx("w1", &["pool"], &["oracle"], 10))
+        .ok();
+    queue.enqueue_or_wait(tx("w2", &["pool"], &[], 10)).ok();
+
+    let unfinished: Vec<String> = queue.reset_slot().into_iter().map(|tx| tx.id).collect();
+    assert_eq!(unfinished, vec!["w1".to_string(), "w2".to_string()]);
+    assert!(queue.is_empty());
+    assert_eq!(queue.waiting_len(), 0);
+    assert!(queue.state().locked_writable.is_empty());
+    assert!(queue.state().locked_readonly.is_empty());
+    assert_eq!(queue.state().consumed_compute_units, 0);
+    assert_eq!(queue.usage().transactions, 0);
+
+    // 새 슬롯에서는 트랜잭션 수 한도가 다시 열린다.
+    assert!(queue.try_enqueue(tx("w1", &["pool"], &[], 10)).is_ok());
+    assert!(queue.try_enqueue(tx("w2", &["other"], &[], 10)).is_ok());
+}
+
+// 수만 개의 트랜잭션을 역순으로 release해도 상태가 깨끗이 비워진다.
+#[test]
+fn test_release_many_in_reverse_order() {
+    let mut queue = ExecutionQueue::new(BlockConstraint {
+        max_compute_units: u32::MAX,
+        max_transactions: 20_000,
+        max_block_compute_units: u32::MAX,
+        max_account_compute_units: u32::MAX,
+    });
+    for i in 0..20_000 {
+        let id = format!("tx-{i}");
+        queue.try_enqueue(tx(&id, &[&id], &["oracle"], 1)).ok();
+    }
+    assert_eq!(queue.len(), 20_000);
+    assert_eq!(
+        queue.state().readonly_holders(&Pubkey::from_name("oracle")),
+        20_000
+    );
+
+    for i in (0..20_000).rev() {
+        queue.release(&format!("tx-{i}"));
+    }
+    assert!(queue.is_empty());
+    assert!(queue.state().locked_readonly.is_empty());
+    assert_eq!(queue.state().consumed_compute_units, 0);
+}
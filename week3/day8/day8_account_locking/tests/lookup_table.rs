@@ -0,0 +1,142 @@
+// 이 테스트 모음은 주소 조회 테이블을 통해 압축 트랜잭션이 올바른 계정 목록으로 펼쳐지는지 확인한다.
+use day8_account_locking::{
+    AddressLookupError, AddressTableLookup, BlockConstraint, CompactTransaction, ExecutionQueue,
+    LookupTableStore, Pubkey, SanitizeError,
+};
+
+fn compact(
+    id: &str,
+    static_writable: Vec<Pubkey>,
+    lookups: Vec<AddressTableLookup>,
+) -> CompactTransaction {
+    CompactTransaction {
+        id: id.to_string(),
+        static_writable,
+        static_readonly: Vec::new(),
+        address_table_lookups: lookups,
+        compute_units: 100,
+    }
+}
+
+fn lookup(table: Pubkey, writable: &[u8], readonly: &[u8]) -> AddressTableLookup {
+    AddressTableLookup {
+        table,
+        writable_indexes: writable.to_vec(),
+        readonly_indexes: readonly.to_vec(),
+    }
+}
+
+// 정적 키 뒤에 테이블 순서대로 쓰기/읽기 주소가 붙는다.
+#[test]
+fn test_resolves_static_and_table_accounts_in_order() {
+    let payer = Pubkey::new_unique();
+    let (table_a, table_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let a: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+    let b: Vec<Pubkey> = (0..2).map(|_| Pubkey::new_unique()).collect();
+    let mut store = LookupTableStore::new();
+    store.insert(table_a, a.clone());
+    store.insert(table_b, b.clone());
+
+    let tx = compact(
+        "swap",
+        vec![payer],
+        vec![lookup(table_a, &[2, 0], &[3]), lookup(table_b, &[1], &[0])],
+    );
+    let meta = store.resolve(&tx).expect("resolved");
+
+    assert_eq!(meta.id, "swap");
+    assert_eq!(meta.writable_accounts, vec![payer, a[2], a[0], b[1]]);
+    assert_eq!(meta.readonly_accounts, vec![a[3], b[0]]);
+    assert_eq!(meta.compute_units, 100);
+}
+
+// 저장소에 없는 테이블을 참조하면 LookupTableNotFound로 실패한다.
+#[test]
+fn test_unknown_table_is_rejected() {
+    let store = LookupTableStore::new();
+    let missing = Pubkey::new_unique();
+    let tx = compact("t", vec![], vec![lookup(missing, &[0], &[])]);
+
+    assert_eq!(
+        store.resolve(&tx).err(),
+        Some(AddressLookupError::LookupTableNotFound { table: missing })
+    );
+}
+
+// 테이블로 가져온 주소가 정적 쓰기 키와 겹치면 펼친 결과가 sanitize에 걸려 InvalidTransaction으로 실패한다.
+#[test]
+fn test_resolved_duplicate_account_is_rejected() {
+    let payer = Pubkey::new_unique();
+    let table = Pubkey::new_unique();
+    let mut store = LookupTableStore::new();
+    store.insert(table, vec![payer]);
+
+    let tx = compact("t", vec![payer], vec![lookup(table, &[0], &[])]);
+    assert_eq!(
+        store.resolve(&tx).err(),
+        Some(AddressLookupError::InvalidTransaction {
+            reason: SanitizeError::DuplicateAccount { account: payer }
+        })
+    );
+}
+
+// 테이블 길이를 벗어난 인덱스는 쓰기/읽기 어느 쪽이든 InvalidLookupIndex로 실패한다.
+#[test]
+fn test_out_of_range_index_is_rejected() {
+    let table = Pubkey::new_unique();
+    let mut store = LookupTableStore::new();
+    store.insert(table, vec![Pubkey::new_unique(), Pubkey::new_unique()]);
+
+    let writable = compact("w", vec![], vec![lookup(table, &[2], &[])]);
+    assert_eq!(
+        store.resolve(&writable).err(),
+        Some(AddressLookupError::InvalidLookupIndex {
+            table,
+            index: 2,
+            len: 2
+        })
+    );
+    let readonly = compact("r", vec![], vec![lookup(table, &[], &[255])]);
+    assert_eq!(
+        store.resolve(&readonly).err(),
+        Some(AddressLookupError::InvalidLookupIndex {
+            table,
+            index: 255,
+            len: 2
+        })
+    );
+}
+
+// 테이블을 extend하면 새 인덱스가 열리고, 펼친 결과로 실제 잠금 충돌을 재현할 수 있다.
+#[test]
+fn test_extended_table_feeds_account_locking() {
+    let table = Pubkey::new_unique();
+    let pool = Pubkey::new_unique();
+    let mut store = LookupTableStore::new();
+    store.extend(table, &[Pubkey::new_unique()]);
+    store.extend(table, &[pool]);
+
+    let first = store
+        .resolve(&compact(
+            "a",
+            vec![Pubkey::new_unique()],
+            vec![lookup(table, &[1], &[])],
+        ))
+        .expect("resolved");
+    let second = store
+        .resolve(&compact(
+            "b",
+            vec![Pubkey::new_unique()],
+            vec![lookup(table, &[1], &[])],
+        ))
+        .expect("resolved");
+
+    let mut queue = ExecutionQueue::new(BlockConstraint {
+        max_compute_units: 1_000,
+        max_transactions: 64,
+        max_block_compute_units: u32::MAX,
+        max_account_compute_units: u32::MAX,
+    });
+    assert!(queue.try_enqueue(first).is_ok());
+    assert!(queue.try_enqueue(second).is_err());
+}
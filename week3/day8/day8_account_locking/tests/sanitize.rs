@@ -0,0 +1,134 @@
+// 이 테스트 모음은 잘못된 TransactionMeta가 경우마다 서로 다른 에러로 걸러지는지 확인한다.
+use day8_account_locking::{
+    AccountLockError, BlockConstraint, ExecutionQueue, MAX_TX_ACCOUNT_LOCKS, Pubkey, SanitizeError,
+    TransactionMeta, sanitize_transaction,
+};
+
+fn tx(id: &str, writable: &[&str], readonly: &[&str], compute_units: u32) -> TransactionMeta {
+    TransactionMeta {
+        id: id.to_string(),
+        writable_accounts: writable.iter().map(|a| Pubkey::from_name(a)).collect(),
+        readonly_accounts: readonly.iter().map(|a| Pubkey::from_name(a)).collect(),
+        compute_units,
+    }
+}
+
+fn queue() -> ExecutionQueue {
+    ExecutionQueue::new(BlockConstraint {
+        max_compute_units: 1_000,
+        max_transactions: 64,
+        max_block_compute_units: u32::MAX,
+        max_account_compute_units: u32::MAX,
+    })
+}
+
+// 정상적인 트랜잭션은 그대로 통과한다.
+#[test]
+fn test_well_formed_transaction_passes() {
+    assert_eq!(tx("t1", &["alice"], &["oracle"], 10).sanitize(), Ok(()));
+}
+
+// id가 비었거나 CU가 0이면 각각 다른 에러로 거절된다.
+#[test]
+fn test_empty_id_and_zero_compute_are_rejected() {
+    assert_eq!(
+        tx("", &["alice"], &[], 10).sanitize(),
+        Err(SanitizeError::EmptyId)
+    );
+    assert_eq!(
+        tx("t1", &["alice"], &[], 0).sanitize(),
+        Err(SanitizeError::ZeroComputeUnits)
+    );
+}
+
+// 같은 목록 안의 중복은 쓰기/읽기 어느 쪽이든 DuplicateAccount로 거절된다.
+#[test]
+fn test_duplicate_account_is_rejected() {
+    assert_eq!(
+        tx("t1", &["alice", "alice"], &[], 10).sanitize(),
+        Err(SanitizeError::DuplicateAccount {
+            account: Pubkey::from_name("alice")
+        })
+    );
+    assert_eq!(
+        tx("t1", &[], &["oracle", "oracle"], 10).sanitize(),
+        Err(SanitizeError::DuplicateAccount {
+            account: Pubkey::from_name("oracle")
+        })
+    );
+}
+
+// 자기 쓰기 계정을 읽기로도 적은 트랜잭션은 잠금 전에 거절된다.
+#[test]
+fn test_writable_and_readonly_overlap_is_rejected() {
+    assert_eq!(
+        tx("t1", &["pool"], &["pool"], 10).sanitize(),
+        Err(SanitizeError::WritableAndReadonly {
+            account: Pubkey::from_name("pool")
+        })
+    );
+}
+
+// 계정 수 한도는 기본 64개이고, 호출자가 바꿀 수 있다.
+#[test]
+fn test_account_key_limit_is_configurable() {
+    let names: Vec<String> = (0..=MAX_TX_ACCOUNT_LOCKS)
+        .map(|i| format!("acc-{i}"))
+        .collect();
+    let refs: Vec<&str> = names.iter().map(String::as_str).collect();
+    let too_many = tx("t1", &refs[..40], &refs[40..], 10);
+
+    assert_eq!(
+        too_many.sanitize(),
+        Err(SanitizeError::TooManyAccountKeys {
+            count: 65,
+            limit: 64
+        })
+    );
+    assert_eq!(sanitize_transaction(&too_many, 128), Ok(()));
+    assert_eq!(
+        sanitize_transaction(&tx("t2", &["a", "b"], &["c"], 10), 2),
+        Err(SanitizeError::TooManyAccountKeys { count: 3, limit: 2 })
+    );
+}
+
+// 대기열 입장 경로도 sanitize를 거치므로, 잘못된 트랜잭션은 잠금을 하나도 잡지 않고 InvalidTransaction으로 거절된다.
+#[test]
+fn test_try_enqueue_rejects_malformed_transaction() {
+    let mut queue = queue();
+    assert_eq!(
+        queue.try_enqueue(tx("t1", &["pool"], &["pool"], 10)),
+        Err(AccountLockError::InvalidTransaction {
+            reason: SanitizeError::WritableAndReadonly {
+                account: Pubkey::from_name("pool")
+            }
+        })
+    );
+    assert!(queue.is_empty());
+    assert!(queue.state().locked_writable.is_empty());
+    assert_eq!(
+        queue.state().readonly_holders(&Pubkey::from_name("pool")),
+        0
+    );
+
+    // 거절된 트랜잭션은 다시 시도할 대상이 아니며, 같은 id의 정상 트랜잭션은 그대로 들어간다.
+    let err = queue.try_enqueue(tx("t1", &["alice"], &[], 0)).unwrap_err();
+    assert!(!err.is_retryable());
+    assert!(queue.try_enqueue(tx("t1", &["alice"], &[], 10)).is_ok());
+}
+
+// enqueue_or_wait도 줄을 서기 전에 같은 검사를 한다.
+#[test]
+fn test_enqueue_or_wait_rejects_malformed_transaction() {
+    let mut queue = queue();
+    assert_eq!(
+        queue.enqueue_or_wait(tx("t1", &["alice", "alice"], &[], 10)),
+        Err(AccountLockError::InvalidTransaction {
+            reason: SanitizeError::DuplicateAccount {
+                account: Pubkey::from_name("alice")
+            }
+        })
+    );
+    assert_eq!(queue.waiting_len(), 0);
+    assert_eq!(queue.usage().transactions, 0);
+}
@@ -0,0 +1,214 @@
+// 이 테스트 모음은 충돌한 트랜잭션이 계정별 FIFO로 기다렸다가 release 시 자동으로 들어가는지 확인한다.
+use day8_account_locking::{
+    AccountLockError, Admission, BlockConstraint, ExecutionQueue, Pubkey, TransactionMeta,
+};
+
+fn tx(id: &str, writable: &[&str], readonly: &[&str], compute_units: u32) -> TransactionMeta {
+    TransactionMeta {
+        id: id.to_string(),
+        writable_accounts: writable.iter().map(|a| Pubkey::from_name(a)).collect(),
+        readonly_accounts: readonly.iter().map(|a| Pubkey::from_name(a)).collect(),
+        compute_units,
+    }
+}
+
+fn queue(max_compute_units: u32) -> ExecutionQueue {
+    ExecutionQueue::new(BlockConstraint {
+        max_compute_units,
+        max_transactions: 64,
+        max_block_compute_units: u32::MAX,
+        max_account_compute_units: u32::MAX,
+    })
+}
+
+// 충돌한 쓰기는 대기했다가, 앞선 보유자가 release하면 그 결과로 id가 돌아온다.
+#[test]
+fn test_conflicting_writer_is_admitted_on_release() {
+    let mut queue = queue(1_000);
+    assert_eq!(
+        queue.enqueue_or_wait(tx("w1", &["pool"], &[], 10)),
+        Ok(Admission::Admitted)
+    );
+    assert_eq!(
+        queue.enqueue_or_wait(tx("w2", &["pool"], &[], 10)),
+        Ok(Admission::Waiting)
+    );
+    assert_eq!(queue.waiting_len(), 1);
+
+    assert_eq!(queue.release_and_admit("w1"), vec!["w2".to_string()]);
+    assert_eq!(queue.waiting_len(), 0);
+    assert!(
+        queue
+            .state()
+            .locked_writable
+            .contains(&Pubkey::from_name("pool"))
+    );
+}
+
+// 기다리는 쓰기가 있으면 뒤이어 온 읽기는 잠금이 가능해도 줄을 선다. (쓰기 기아 방지)
+#[test]
+fn test_writer_is_not_starved_by_later_readers() {
+    let mut queue = queue(1_000);
+    queue.enqueue_or_wait(tx("r1", &[], &["pool"], 10)).ok();
+    assert_eq!(
+        queue.enqueue_or_wait(tx("w", &["pool"], &[], 10)),
+        Ok(Admission::Waiting)
+    );
+    assert_eq!(
+        queue.enqueue_or_wait(tx("r2", &[], &["pool"], 10)),
+        Ok(Admission::Waiting)
+    );
+    assert_eq!(
+        queue.enqueue_or_wait(tx("r3", &[], &["pool"], 10)),
+        Ok(Admission::Waiting)
+    );
+
+    // 첫 읽기가 끝나면 쓰기가 먼저 들어가고, 뒤의 읽기들은 계속 기다린다.
+    assert_eq!(queue.release_and_admit("r1"), vec!["w".to_string()]);
+    // 쓰기가 끝나면 대기하던 읽기들이 한 번에 들어간다.
+    assert_eq!(
+        queue.release_and_admit("w"),
+        vec!["r2".to_string(), "r3".to_string()]
+    );
+    assert_eq!(
+        queue.state().readonly_holders(&Pubkey::from_name("pool")),
+        2
+    );
+}
+
+// 다른 계정만 쓰는 트랜잭션은 대기열과 무관하게 바로 들어간다.
+#[test]
+fn test_unrelated_transaction_skips_wait_list() {
+    let mut queue = queue(1_000);
+    queue.enqueue_or_wait(tx("w1", &["pool"], &[], 10)).ok();
+    queue.enqueue_or_wait(tx("w2", &["pool"], &[], 10)).ok();
+    assert_eq!(
+        queue.enqueue_or_wait(tx("a", &["alice"], &[], 10)),
+        Ok(Admission::Admitted)
+    );
+}
+
+// 같은 계정의 대기자는 도착 순서(FIFO)대로 하나씩 들어간다.
+#[test]
+fn test_writers_are_admitted_in_fifo_order() {
+    let mut queue = queue(1_000);
+    queue.enqueue_or_wait(tx("w0", &["pool"], &[], 10)).ok();
+    for id in ["w1", "w2", "w3"] {
+        queue.enqueue_or_wait(tx(id, &["pool"], &[], 10)).ok();
+    }
+
+    assert_eq!(queue.release_and_admit("w0"), vec!["w1".to_string()]);
+    assert_eq!(queue.release_and_admit("w1"), vec!["w2".to_string()]);
+    assert_eq!(queue.release_and_admit("w2"), vec!["w3".to_string()]);
+    assert!(queue.release_and_admit("w3").is_empty());
+}
+
+// 여러 계정을 쓰는 대기자는 모든 계정이 풀릴 때까지 기다리고, 그동안 충돌하는 뒷사람이 추월하지 못한다.
+#[test]
+fn test_multi_account_waiter_keeps_its_place() {
+    let mut queue = queue(1_000);
+    queue.enqueue_or_wait(tx("a", &["alice"], &[], 10)).ok();
+    queue.enqueue_or_wait(tx("b", &["bob"], &[], 10)).ok();
+    assert_eq!(
+        queue.enqueue_or_wait(tx("ab", &["alice", "bob"], &[], 10)),
+        Ok(Admission::Waiting)
+    );
+    assert_eq!(
+        queue.enqueue_or_wait(tx("a2", &["alice"], &[], 10)),
+        Ok(Admission::Waiting)
+    );
+
+    assert!(queue.release_and_admit("a").is_empty());
+    assert_eq!(queue.release_and_admit("b"), vec!["ab".to_string()]);
+    assert_eq!(queue.release_and_admit("ab"), vec!["a2".to_string()]);
+}
+
+// CU가 부족하면 기다렸다가 CU가 풀릴 때 들어가고, 혼자서도 제한을 넘으면 거절한다.
+#[test]
+fn test_compute_budget_waits_or_rejects() {
+    let mut queue = queue(100);
+    queue.enqueue_or_wait(tx("big", &["alice"], &[], 80)).ok();
+    assert_eq!(
+        queue.enqueue_or_wait(tx("next", &["bob"], &[], 50)),
+        Ok(Admission::Waiting)
+    );
+    assert_eq!(
+        queue.enqueue_or_wait(tx("huge", &["carol"], &[], 200)),
+        Err(AccountLockError::ComputeLimitExceeded {
+            requested: 200,
+            limit: 100
+        })
+    );
+
+    assert_eq!(queue.release_and_admit("big"), vec!["next".to_string()]);
+}
+
+// release도 내부적으로 대기자를 들여보낸다.
+#[test]
+fn test_plain_release_admits_waiters() {
+    let mut queue = queue(1_000);
+    queue.enqueue_or_wait(tx("w1", &["pool"], &[], 10)).ok();
+    queue.enqueue_or_wait(tx("w2", &["pool"], &[], 10)).ok();
+
+    queue.release("w1");
+    assert_eq!(queue.waiting_len(), 0);
+    assert_eq!(queue.len(), 1);
+}
+
+// 대기열에 줄 선 트랜잭션도 슬롯 한도를 미리 예약하므로, 한도를 넘으면 줄을 서기 전에 거절된다.
+#[test]
+fn test_waiters_reserve_slot_limits() {
+    let mut queue = ExecutionQueue::new(BlockConstraint {
+        max_compute_units: 1_000,
+        max_transactions: 2,
+        max_block_compute_units: u32::MAX,
+        max_account_compute_units: u32::MAX,
+    });
+    queue.enqueue_or_wait(tx("w1", &["pool"], &[], 10)).ok();
+    assert_eq!(
+        queue.enqueue_or_wait(tx("w2", &["pool"], &[], 10)),
+        Ok(Admission::Waiting)
+    );
+    assert_eq!(
+        queue.enqueue_or_wait(tx("w3", &["pool"], &[], 10)),
+        Err(AccountLockError::TransactionLimitReached { limit: 2 })
+    );
+}
+
+// try_enqueue도 줄 선 대기자와 충돌하면 거절하므로, 두 방식을 섞어도 기다리던 쓰기를 추월하지 못한다.
+#[test]
+fn test_try_enqueue_does_not_overtake_waiters() {
+    let mut queue = queue(1_000);
+    queue.enqueue_or_wait(tx("r1", &[], &["pool"], 10)).ok();
+    assert_eq!(
+        queue.enqueue_or_wait(tx("w", &["pool"], &[], 10)),
+        Ok(Admission::Waiting)
+    );
+
+    assert_eq!(
+        queue.try_enqueue(tx("r2", &[], &["pool"], 10)),
+        Err(AccountLockError::Conflict {
+            account: Pubkey::from_name("pool")
+        })
+    );
+    assert!(queue.try_enqueue(tx("a", &["alice"], &[], 10)).is_ok());
+    assert_eq!(queue.release_and_admit("r1"), vec!["w".to_string()]);
+}
+
+// 충돌로 기다리다 CU 때문에 다시 막힌 대기자는, 자기 계정과 무관한 트랜잭션의 release로도 들어간다.
+#[test]
+fn test_compute_blocked_waiter_is_admitted_by_unrelated_release() {
+    let mut queue = queue(100);
+    queue.enqueue_or_wait(tx("w1", &["pool"], &[], 40)).ok();
+    assert_eq!(
+        queue.enqueue_or_wait(tx("w2", &["pool"], &[], 50)),
+        Ok(Admission::Waiting)
+    );
+    assert_eq!(
+        queue.enqueue_or_wait(tx("x", &["alice"], &[], 60)),
+        Ok(Admission::Admitted)
+    );
+
+    assert!(queue.release_and_admit("w1").is_empty());
+    assert_eq!(queue.release_and_admit("x"), vec!["w2".to_string()]);
+}
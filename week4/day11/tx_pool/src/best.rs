@@ -0,0 +1,177 @@
+//! 블록 빌더용 비파괴 best 반복자입니다.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap, btree_set};
+use std::iter::{Peekable, Rev};
+
+use crate::{BestRank, HeadKey, PendingTransaction, Pubkey, TipCap, TxPool, best_rank};
+
+impl TxPool {
+    /// pending 트랜잭션을 pop_batch와 같은 순서로 하나씩 보여 주는 비파괴 반복자를 돌려줍니다. (reth의 `BestTransactions`)
+    /// 반복자가 풀을 빌리는 동안 풀은 바뀌지 않으며, 블록에 실제로 담은 트랜잭션은 on_canonical_block으로 반영합니다.
+    pub fn best_transactions(&self) -> BestTransactions<'_> {
+        BestTransactions {
+            pool: self,
+            by_priority_fee: self.global_queue.by_priority_fee.iter().rev().peekable(),
+            by_max_fee: self.global_queue.by_max_fee.iter().rev().peekable(),
+            successors: BTreeSet::new(),
+            invalid: HashMap::new(),
+        }
+    }
+}
+
+/// `TxPool::best_transactions`가 돌려주는 반복자입니다.
+/// sender별 head는 global_queue 두 집합을 높은 쪽부터 훑어 그대로 쓰고, 내보낸 sender의 다음 nonce만
+/// successors에 새로 넣어 세 후보 중 가장 높은 것을 고릅니다. 처음 만들 때 풀 전체를 복사하지 않습니다.
+pub struct BestTransactions<'a> {
+    pool: &'a TxPool,
+    by_priority_fee: Peekable<Rev<btree_set::Iter<'a, HeadKey>>>,
+    by_max_fee: Peekable<Rev<btree_set::Iter<'a, HeadKey>>>,
+    /// 이미 내보낸 트랜잭션의 다음 pending nonce입니다.
+    successors: BTreeSet<BestRank>,
+    /// sender → 이 nonce부터는 내보내지 않는다는 표시입니다.
+    invalid: HashMap<Pubkey, u64>,
+}
+
+impl BestTransactions<'_> {
+    /// 블록에 넣을 수 없는 트랜잭션을 알립니다. 그 트랜잭션과 같은 sender의 더 높은 nonce는 더 이상 나오지 않습니다.
+    pub fn mark_invalid(&mut self, tx: &PendingTransaction) {
+        self.invalid
+            .entry(tx.sender)
+            .and_modify(|from| *from = (*from).min(tx.nonce))
+            .or_insert(tx.nonce);
+    }
+
+    /// 세 후보 중 순위가 가장 높은 `(sender, nonce)`를 꺼냅니다.
+    fn pop_best(&mut self) -> Option<(Pubkey, u64)> {
+        let base_fee = self.pool.base_fee;
+        let candidates = [
+            self.by_priority_fee
+                .peek()
+                .map(|head| head.best_rank(TipCap::PriorityFee, base_fee)),
+            self.by_max_fee
+                .peek()
+                .map(|head| head.best_rank(TipCap::MaxFee, base_fee)),
+            self.successors.last().copied(),
+        ];
+        let (source, (_, _, Reverse(nonce), sender)) = candidates
+            .into_iter()
+            .enumerate()
+            .filter_map(|(source, rank)| Some((source, rank?)))
+            .max_by(|(_, a), (_, b)| a.cmp(b))?;
+        match source {
+            0 => {
+                self.by_priority_fee.next();
+            }
+            1 => {
+                self.by_max_fee.next();
+            }
+            _ => {
+                self.successors.pop_last();
+            }
+        }
+        Some((sender, nonce))
+    }
+}
+
+impl Iterator for BestTransactions<'_> {
+    type Item = PendingTransaction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (sender, nonce) = self.pop_best()?;
+            // invalid로 표시된 sender는 다음 nonce를 successors에 넣지 않아 사슬이 여기서 끊깁니다.
+            if self.invalid.get(&sender).is_some_and(|&from| nonce >= from) {
+                continue;
+            }
+            let queue = &self.pool.per_account[&sender];
+            if let Some(next) = queue.pending_txs().find(|next| next.nonce == nonce + 1) {
+                self.successors.insert(best_rank(next, self.pool.base_fee));
+            }
+            return Some(queue.txs[&nonce].clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use crate::{CanonicalBlock, TxPoolConfig};
+
+    #[test]
+    fn best_transactions_follows_pop_order_without_mutating() {
+        // Given: base fee 40에서 팁·max_fee가 섞인 여러 sender, parked·queued 포함
+        let mut pool = TxPool::new(TxPoolConfig::default());
+        pool.set_base_fee(40);
+        pool.insert(make_1559_tx("alice", 0, 100, 50, "a0"))
+            .unwrap();
+        pool.insert(make_1559_tx("alice", 1, 200, 5, "a1")).unwrap();
+        pool.insert(make_1559_tx("alice", 2, 100, 70, "a2"))
+            .unwrap();
+        pool.insert(make_1559_tx("bob", 0, 70, 20, "b0")).unwrap();
+        pool.insert(make_1559_tx("bob", 1, 300, 30, "b1")).unwrap();
+        pool.insert(make_1559_tx("carol", 0, 30, 30, "c0")).unwrap();
+        pool.insert(make_1559_tx("dave", 0, 90, 10, "d0")).unwrap();
+        pool.insert(make_1559_tx("dave", 2, 90, 90, "d2")).unwrap();
+        // When: best_transactions를 끝까지 읽음
+        let best: Vec<String> = pool.best_transactions().map(|tx| tx.hash).collect();
+        // Then: 풀은 그대로이고, pending만 pop_batch(1)을 반복한 순서와 같게 나옴
+        // (alice 1은 팁이 5라 뒤로 밀리고, 그 뒤를 잇는 alice 2는 alice 1이 나온 다음에야 후보가 됨)
+        assert_eq!(pool.len(), 8);
+        assert_eq!(pool.pending_len(), 6);
+        let mut popped = Vec::new();
+        while let [hash] = drain_hashes(&mut pool, 1).as_slice() {
+            popped.push(hash.clone());
+        }
+        assert_eq!(best, popped);
+        assert_eq!(
+            best,
+            vec![
+                "alice-0-a0",
+                "bob-0-b0",
+                "bob-1-b1",
+                "dave-0-d0",
+                "alice-1-a1",
+                "alice-2-a2",
+            ]
+        );
+    }
+
+    #[test]
+    fn mark_invalid_skips_rest_of_sender_chain() {
+        // Given: alice 0·1·2(gas 100), bob 0·1(gas 50)
+        let mut pool = TxPool::new(TxPoolConfig::default());
+        for nonce in 0..3 {
+            pool.insert(make_tx("alice", nonce, 0, 100, "a")).unwrap();
+        }
+        for nonce in 0..2 {
+            pool.insert(make_tx("bob", nonce, 0, 50, "b")).unwrap();
+        }
+        // When: alice 1을 블록에 담지 못해 invalid로 표시
+        let mut best = pool.best_transactions();
+        let mut included = Vec::new();
+        while let Some(tx) = best.next() {
+            if tx.hash == "alice-1-a" {
+                best.mark_invalid(&tx);
+                continue;
+            }
+            included.push(tx.hash);
+        }
+        // Then: alice 2는 나오지 않고 bob은 계속 나오며, 풀은 builder가 반영하기 전까지 그대로
+        assert_eq!(included, vec!["alice-0-a", "bob-0-b", "bob-1-b"]);
+        assert_eq!(pool.len(), 5);
+        // When: 실제로 담은 트랜잭션만 블록으로 반영
+        pool.on_canonical_block(CanonicalBlock {
+            mined: vec![
+                (Pubkey::from_name("alice"), 0),
+                (Pubkey::from_name("bob"), 0),
+                (Pubkey::from_name("bob"), 1),
+            ],
+            ..CanonicalBlock::default()
+        });
+        // Then: invalid였던 alice 1부터 다시 후보가 됨
+        let next: Vec<String> = pool.best_transactions().map(|tx| tx.hash).collect();
+        assert_eq!(next, vec!["alice-1-a", "alice-2-a"]);
+    }
+}
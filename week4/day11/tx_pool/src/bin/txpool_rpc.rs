@@ -0,0 +1,17 @@
+//! 로컬 개발용 tx_pool JSON-RPC 서버입니다. 첫 인자로 바인딩 주소를 받고, 없으면 127.0.0.1:8545를 씁니다.
+use std::io;
+
+use tx_pool::rpc::RpcServer;
+use tx_pool::{TxPoolConfig, TxPoolHandle};
+
+fn main() -> io::Result<()> {
+    let addr = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:8545".to_string());
+    let server = RpcServer::bind(&addr, TxPoolHandle::new(TxPoolConfig::default(), 8))?;
+    println!(
+        "tx_pool JSON-RPC listening on http://{}",
+        server.local_addr()?
+    );
+    server.serve()
+}
@@ -0,0 +1,412 @@
+//! 여러 스레드가 공유하는 sharded TxPool 핸들입니다.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, mpsc};
+use std::time::Instant;
+
+use crate::persist::{fnv1a, read_snapshot, write_snapshot};
+use crate::{
+    CanonicalBlock, CanonicalUpdate, InsertOutcome, LoadReport, PendingTransaction, PersistError,
+    PoolEvent, PopResult, PruneOutcome, Pubkey, SubPool, TxInsertError, TxPool, TxPoolConfig,
+    sort_drained,
+};
+
+/// 여러 스레드가 함께 쓰는 TxPool 핸들입니다. clone은 같은 풀을 가리킵니다.
+/// sender 해시로 나눈 shard마다 독립된 TxPool과 Mutex를 두어, 서로 다른 shard의 insert는 동시에 진행됩니다.
+/// 한 sender의 트랜잭션은 항상 같은 shard에 있으므로 nonce 순서·교체·슬롯 규칙은 shard 안에서 그대로 지켜집니다.
+/// capacity와 축출은 shard 단위로 적용되므로, 풀 전체 한도는 shard별 capacity의 합입니다.
+/// 전역 한도가 아니므로 한 shard가 가득 차면 다른 shard가 비어 있어도 그 shard의 sender는 PoolFull을 받습니다.
+///
+/// `best_transactions`는 제공하지 않습니다. 반복자가 모든 shard 잠금을 쥔 채 풀을 빌려야 해서 그동안 insert가 모두 멈추기
+/// 때문입니다. 블록 빌더는 `pop_batch`로 꺼내거나, 단일 TxPool을 직접 써야 합니다.
+#[derive(Clone)]
+pub struct TxPoolHandle {
+    shards: Arc<[Mutex<TxPool>]>,
+}
+
+impl TxPoolHandle {
+    /// shard마다 같은 config로 TxPool::new를 만듭니다.
+    /// config의 capacity·local_capacity는 shard 하나의 한도이므로 핸들 전체 한도는 `shards`배가 됩니다.
+    pub fn new(config: TxPoolConfig, shards: usize) -> Self {
+        let shards = shards.max(1);
+        Self::from_shards((0..shards).map(|_| TxPool::new(config.clone())).collect())
+    }
+
+    /// 직접 만든 풀들을 shard로 씁니다. nonce 조회기·validator·시계를 붙일 때 씁니다. (조회기는 Arc로 공유 가능)
+    pub fn from_shards(pools: Vec<TxPool>) -> Self {
+        assert!(!pools.is_empty(), "shard가 하나 이상 있어야 합니다");
+        Self {
+            shards: pools.into_iter().map(Mutex::new).collect(),
+        }
+    }
+
+    /// sender가 속한 shard 하나만 잠그고 삽입합니다.
+    pub fn insert(&self, tx: PendingTransaction) -> Result<InsertOutcome, TxInsertError> {
+        self.shard(&tx.sender).insert(tx)
+    }
+
+    /// shard head 중 가장 높은 것을 하나씩 꺼내 최대 `limit`개를 돌려줍니다. 순서는 단일 TxPool의 pop_batch와 같습니다.
+    /// shard별 head 순위를 한 번씩 읽어 두고, 꺼낸 shard의 순위만 그 잠금 안에서 다시 읽는 k-way merge입니다.
+    /// 한 번에 shard 하나만 잠깐 잠그므로, 블록 빌더가 꺼내는 동안에도 다른 shard의 insert는 기다리지 않습니다.
+    /// 도중에 들어온 트랜잭션은 그 shard에서 다음에 꺼낼 때 반영됩니다.
+    pub fn pop_batch(&self, limit: usize) -> PopResult {
+        let mut ranks: Vec<_> = self
+            .shards
+            .iter()
+            .map(|shard| lock(shard).best_head_rank())
+            .collect();
+        let mut drained = Vec::new();
+        let mut base_fee = None;
+        while drained.len() < limit {
+            let Some((_, best)) = ranks
+                .iter()
+                .enumerate()
+                .filter_map(|(index, rank)| Some((rank.as_ref()?, index)))
+                .max()
+            else {
+                break;
+            };
+            let mut shard = lock(&self.shards[best]);
+            if let PopResult::Batch { drained: popped } = shard.pop_batch(1) {
+                drained.extend(popped);
+            }
+            ranks[best] = shard.best_head_rank();
+            base_fee = Some(shard.base_fee);
+        }
+
+        match base_fee {
+            Some(base_fee) if !drained.is_empty() => {
+                sort_drained(&mut drained, base_fee);
+                PopResult::Batch { drained }
+            }
+            _ => PopResult::Empty,
+        }
+    }
+
+    /// 모든 shard의 base fee를 바꾸고 새로 pending에 오른 hash를 돌려줍니다.
+    pub fn set_base_fee(&self, base_fee: u64) -> Vec<String> {
+        self.lock_all()
+            .iter_mut()
+            .flat_map(|shard| shard.set_base_fee(base_fee))
+            .collect()
+    }
+
+    /// canonical 블록을 shard별로 나눠 반영합니다. base fee는 모든 shard에 적용합니다.
+    pub fn on_canonical_block(&self, block: CanonicalBlock) -> CanonicalUpdate {
+        let mut shards = self.lock_all();
+        let mut update = CanonicalUpdate::default();
+        for (shard, part) in shards.iter_mut().zip(self.split_block(block)) {
+            merge_update(&mut update, shard.on_canonical_block(part));
+        }
+        update
+    }
+
+    /// reorg를 shard별로 나눠 반영합니다. 되돌린 트랜잭션은 sender의 shard로 보내 TxPool::on_reorg와 같은 규칙으로 재삽입합니다.
+    /// 결과 목록은 shard 순서로 이어 붙이므로, 한 sender 안의 순서만 TxPool::on_reorg와 같습니다.
+    pub fn on_reorg(
+        &self,
+        reverted: Vec<PendingTransaction>,
+        new_block: CanonicalBlock,
+    ) -> CanonicalUpdate {
+        let mut shards = self.lock_all();
+        let mut reverted_parts: Vec<Vec<PendingTransaction>> = vec![Vec::new(); shards.len()];
+        for tx in reverted {
+            reverted_parts[self.shard_index(&tx.sender)].push(tx);
+        }
+
+        let mut update = CanonicalUpdate::default();
+        for ((shard, part), reverted) in shards
+            .iter_mut()
+            .zip(self.split_block(new_block))
+            .zip(reverted_parts)
+        {
+            merge_update(&mut update, shard.on_reorg(reverted, part));
+        }
+        update
+    }
+
+    /// 모든 shard의 이벤트를 한 수신기로 모아 돌려줍니다. shard 사이의 이벤트 순서는 잠금을 얻은 순서를 따릅니다.
+    pub fn subscribe(&self) -> mpsc::Receiver<PoolEvent> {
+        let (sender, receiver) = mpsc::channel();
+        for mut shard in self.lock_all() {
+            shard.add_listener(sender.clone());
+        }
+        receiver
+    }
+
+    /// 모든 shard를 잠근 한 시점의 내용을 단일 TxPool과 같은 형식의 스냅숏 하나로 저장합니다.
+    /// 따라서 shard 수가 다른 핸들이나 단일 TxPool로도 불러올 수 있습니다.
+    pub fn save_to(&self, path: impl AsRef<Path>) -> Result<(), PersistError> {
+        let shards = self.lock_all();
+        let pools: Vec<&TxPool> = shards.iter().map(|shard| &**shard).collect();
+        write_snapshot(path.as_ref(), &pools)
+    }
+
+    /// 스냅숏을 sender의 shard로 나눠 TxPool::restore_from과 같은 규칙으로 다시 넣습니다.
+    pub fn restore_from(&self, path: impl AsRef<Path>) -> Result<LoadReport, PersistError> {
+        let snapshot = read_snapshot(path.as_ref())?;
+        let mut shards = self.lock_all();
+        let mut state_nonces: Vec<Vec<(Pubkey, u64)>> = vec![Vec::new(); shards.len()];
+        for (sender, nonce) in snapshot.state_nonces {
+            state_nonces[self.shard_index(&sender)].push((sender, nonce));
+        }
+        for (shard, state_nonces) in shards.iter_mut().zip(state_nonces) {
+            shard.restore_state(snapshot.base_fee, state_nonces);
+        }
+
+        let mut report = LoadReport {
+            corrupted: snapshot.corrupted,
+            ..LoadReport::default()
+        };
+        for tx in snapshot.txs {
+            let result = shards[self.shard_index(&tx.sender)].insert(tx.clone());
+            report.record(tx, result);
+        }
+        Ok(report)
+    }
+
+    /// 모든 shard에서 만료 트랜잭션을 정리합니다.
+    pub fn prune_expired(&self, now: Instant) -> PruneOutcome {
+        let mut outcome = PruneOutcome::default();
+        for mut shard in self.lock_all() {
+            let shard_outcome = shard.prune_expired(now);
+            outcome.expired.extend(shard_outcome.expired);
+            outcome.dependents.extend(shard_outcome.dependents);
+        }
+        outcome
+    }
+
+    /// 전체 트랜잭션 수입니다. shard를 하나씩 잠그므로 동시 삽입 중에는 근삿값입니다.
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| lock(shard).len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 전체 pending 트랜잭션 수입니다. len과 같은 이유로 동시 삽입 중에는 근삿값입니다.
+    pub fn pending_len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| lock(shard).pending_len())
+            .sum()
+    }
+
+    /// base fee 미달로 멈춰 있는 parked 트랜잭션 수입니다.
+    pub fn parked_len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| lock(shard).parked_len())
+            .sum()
+    }
+
+    /// nonce 빈틈 때문에 대기 중인 queued 트랜잭션 수입니다.
+    pub fn queued_len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| lock(shard).queued_len())
+            .sum()
+    }
+
+    /// 모든 shard를 잠근 한 시점의 `(pending, parked, queued)` 수입니다.
+    /// 개별 `*_len`을 차례로 부르면 그 사이 insert·pop이 끼어들어 합계가 어느 시점과도 맞지 않을 수 있습니다.
+    pub fn sub_pool_lens(&self) -> (usize, usize, usize) {
+        self.lock_all()
+            .iter()
+            .fold((0, 0, 0), |(pending, parked, queued), shard| {
+                (
+                    pending + shard.pending_len(),
+                    parked + shard.parked_len(),
+                    queued + shard.queued_len(),
+                )
+            })
+    }
+
+    /// 모든 shard를 잠근 한 시점의 트랜잭션 복사본이며 `(sender, nonce)` 오름차순입니다.
+    pub fn transactions(&self) -> Vec<(SubPool, PendingTransaction)> {
+        let mut transactions: Vec<(SubPool, PendingTransaction)> = self
+            .lock_all()
+            .iter()
+            .flat_map(|shard| {
+                shard
+                    .transactions()
+                    .map(|(sub_pool, tx)| (sub_pool, tx.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        transactions.sort_by_key(|(_, tx)| (tx.sender, tx.nonce));
+        transactions
+    }
+
+    /// 블록의 mined·account_nonces를 sender의 shard별로 나눕니다. base fee는 모든 조각에 그대로 둡니다.
+    fn split_block(&self, block: CanonicalBlock) -> Vec<CanonicalBlock> {
+        let mut parts: Vec<CanonicalBlock> = (0..self.shards.len())
+            .map(|_| CanonicalBlock {
+                base_fee: block.base_fee,
+                ..CanonicalBlock::default()
+            })
+            .collect();
+        for (sender, nonce) in block.mined {
+            parts[self.shard_index(&sender)].mined.push((sender, nonce));
+        }
+        for (sender, nonce) in block.account_nonces {
+            parts[self.shard_index(&sender)]
+                .account_nonces
+                .insert(sender, nonce);
+        }
+        parts
+    }
+
+    fn shard_index(&self, sender: &Pubkey) -> usize {
+        (fnv1a(sender.as_bytes()) % self.shards.len() as u64) as usize
+    }
+
+    fn shard(&self, sender: &Pubkey) -> MutexGuard<'_, TxPool> {
+        lock(&self.shards[self.shard_index(sender)])
+    }
+
+    /// 여러 shard를 잡을 때는 항상 인덱스 순서로 잠가, 한 shard만 잡는 insert와 교착되지 않게 합니다.
+    pub(crate) fn lock_all(&self) -> Vec<MutexGuard<'_, TxPool>> {
+        self.shards.iter().map(lock).collect()
+    }
+}
+
+fn merge_update(update: &mut CanonicalUpdate, shard_update: CanonicalUpdate) {
+    update.mined.extend(shard_update.mined);
+    update.discarded.extend(shard_update.discarded);
+    update.promoted.extend(shard_update.promoted);
+    update.reinjected.extend(shard_update.reinjected);
+}
+
+/// 다른 스레드가 풀을 바꾸다 패닉했다면 풀 불변식을 믿을 수 없으므로 같이 패닉합니다.
+fn lock(shard: &Mutex<TxPool>) -> MutexGuard<'_, TxPool> {
+    shard
+        .lock()
+        .expect("다른 스레드가 shard를 수정하다 패닉했습니다")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use std::collections::HashMap;
+    use std::fs;
+
+    #[test]
+    fn handle_keeps_invariants_under_concurrent_insert_and_pop() {
+        // Given: 8개 shard 핸들, 생산자 8스레드 × sender 25명 × nonce 40개
+        const PRODUCERS: usize = 8;
+        const SENDERS: usize = 25;
+        const NONCES: u64 = 40;
+        let config = TxPoolConfig {
+            capacity: 100_000,
+            max_account_slots: 1_000,
+            ..TxPoolConfig::default()
+        };
+        let handle = TxPoolHandle::new(config, 8);
+        let producing = std::sync::atomic::AtomicUsize::new(PRODUCERS);
+        let mut batches: Vec<Vec<PendingTransaction>> = Vec::new();
+
+        // When: 생산자들이 sender를 번갈아 가며 insert하는 동안 builder가 pop_batch로 꺼냄
+        std::thread::scope(|scope| {
+            for producer in 0..PRODUCERS {
+                let handle = handle.clone();
+                let producing = &producing;
+                scope.spawn(move || {
+                    for nonce in 0..NONCES {
+                        for sender in 0..SENDERS {
+                            let gas = 1 + (nonce * 7 + sender as u64 * 13) % 97;
+                            let tx = make_tx(&format!("p{producer}-s{sender}"), nonce, 0, gas, "t");
+                            handle
+                                .insert(tx)
+                                .expect("capacity가 넉넉하므로 모두 들어가야 합니다");
+                        }
+                    }
+                    producing.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+                });
+            }
+            scope.spawn(|| {
+                loop {
+                    let finished = producing.load(std::sync::atomic::Ordering::SeqCst) == 0;
+                    match handle.pop_batch(64) {
+                        PopResult::Batch { drained } => batches.push(drained),
+                        PopResult::Empty if finished => break,
+                        PopResult::Empty => std::thread::yield_now(),
+                    }
+                    for shard in handle.lock_all() {
+                        shard.assert_invariants();
+                    }
+                }
+            });
+        });
+
+        // Then: 모든 트랜잭션이 한 번씩 나오고, sender마다 이전 배치보다 높은 nonce만 이어서 나옴
+        assert!(handle.is_empty());
+        let mut popped: HashMap<Pubkey, Vec<u64>> = HashMap::new();
+        for batch in &batches {
+            let mut in_batch: HashMap<Pubkey, Vec<u64>> = HashMap::new();
+            for tx in batch {
+                in_batch.entry(tx.sender).or_default().push(tx.nonce);
+            }
+            for (sender, mut nonces) in in_batch {
+                nonces.sort_unstable();
+                let seen = popped.entry(sender).or_default();
+                assert_eq!(nonces[0], seen.len() as u64);
+                seen.extend(nonces);
+            }
+        }
+        assert_eq!(popped.len(), PRODUCERS * SENDERS);
+        assert!(
+            popped
+                .values()
+                .all(|nonces| nonces.iter().copied().eq(0..NONCES))
+        );
+    }
+
+    #[test]
+    fn handle_forwards_reorg_subscription_and_snapshots() {
+        // Given: 4개 shard 핸들을 구독하고 alice 0·1, bob 0을 넣은 뒤 alice 0·1을 pop_batch로 내보냄
+        let handle = TxPoolHandle::new(TxPoolConfig::default(), 4);
+        let events = handle.subscribe();
+        let a0 = make_tx("alice", 0, 5, 100, "a0");
+        let a1 = make_tx("alice", 1, 5, 100, "a1");
+        handle.insert(a0.clone()).unwrap();
+        handle.insert(a1.clone()).unwrap();
+        handle.insert(make_tx("bob", 0, 0, 10, "b0")).unwrap();
+        handle.pop_batch(2);
+        // When: 새 체인은 alice 0만 포함하고 1은 되돌려짐
+        let update = handle.on_reorg(
+            vec![a0, a1],
+            CanonicalBlock {
+                account_nonces: HashMap::from([(Pubkey::from_name("alice"), 1)]),
+                ..CanonicalBlock::default()
+            },
+        );
+        // Then: 1만 재삽입되고, 모든 shard의 이벤트가 한 수신기로 모임
+        assert_eq!(update.reinjected, vec!["alice-1-a1"]);
+        assert_eq!(
+            events.try_iter().collect::<Vec<_>>(),
+            vec![
+                added("alice-0-a0", SubPool::Pending),
+                added("alice-1-a1", SubPool::Pending),
+                added("bob-0-b0", SubPool::Pending),
+                added("alice-1-a1", SubPool::Pending),
+            ]
+        );
+
+        // When: 핸들 스냅숏을 shard 수가 다른 핸들로 복원
+        let path = snapshot_path("handle");
+        handle.save_to(&path).unwrap();
+        let restored = TxPoolHandle::new(TxPoolConfig::default(), 2);
+        let report = restored.restore_from(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        // Then: 트랜잭션과 함께 앞당겨진 alice state nonce도 돌아와 alice 1이 pending
+        assert_eq!(report.restored, vec!["alice-1-a1", "bob-0-b0"]);
+        assert_eq!(restored.pending_len(), 2);
+        assert!(matches!(
+            restored.insert(make_tx("alice", 0, 5, 200, "a0-again")),
+            Err(TxInsertError::NonceTooLow { state_nonce: 1, .. })
+        ));
+    }
+}
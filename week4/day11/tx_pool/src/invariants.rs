@@ -0,0 +1,202 @@
+//! 테스트·디버깅용 풀 불변식 검사입니다.
+
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Debug;
+use std::time::Instant;
+
+use crate::{CappedSet, HeadKey, Pubkey, SenderKeys, TailKey, TxPool, reindex};
+
+impl TxPool {
+    /// 증분으로 유지하는 카운터·인덱스·정렬 집합을 sender 큐에서 처음부터 다시 계산해 비교하고, 어긋나면 panic합니다.
+    /// 풀 전체를 훑는 O(n log n) 검사라 테스트·디버깅 전용입니다. 운영 경로에서는 부르지 않습니다.
+    pub fn assert_invariants(&self) {
+        let mut total = 0;
+        let mut local = 0;
+        let mut hashes = HashSet::new();
+        let mut expected = TxPoolIndexes::default();
+
+        for (sender, queue) in &self.per_account {
+            assert!(
+                !queue.is_empty(),
+                "빈 큐가 per_account에 남아 있습니다: {sender}"
+            );
+            assert_eq!(
+                queue.local,
+                self.config.local_senders.contains(sender),
+                "로컬 여부가 config와 다릅니다: {sender}"
+            );
+            for (&nonce, tx) in &queue.txs {
+                assert_eq!(
+                    (tx.sender, tx.nonce),
+                    (*sender, nonce),
+                    "큐 위치와 트랜잭션 필드가 다릅니다: {}",
+                    tx.hash
+                );
+                assert!(hashes.insert(tx.hash.as_str()), "중복 hash: {}", tx.hash);
+            }
+            // BTreeMap 키 순서가 곧 실행 순서이므로 필드 nonce도 엄격히 증가해야 합니다.
+            assert!(
+                queue
+                    .txs
+                    .values()
+                    .zip(queue.txs.values().skip(1))
+                    .all(|(prev, next)| prev.nonce < next.nonce),
+                "nonce가 엄격히 증가하지 않습니다: {sender}"
+            );
+            assert!(
+                queue.inserted_at.keys().eq(queue.txs.keys()),
+                "삽입 시각과 트랜잭션의 nonce 집합이 다릅니다: {sender}"
+            );
+
+            let state_nonce = *self
+                .state_nonces
+                .get(sender)
+                .unwrap_or_else(|| panic!("풀에 있는 sender의 state nonce가 없습니다: {sender}"));
+            assert!(
+                queue.txs.keys().all(|&nonce| nonce >= state_nonce),
+                "state nonce {state_nonce} 아래 트랜잭션이 남아 있습니다: {sender}"
+            );
+            assert_eq!(
+                (queue.pending, queue.parked),
+                queue.boundaries(state_nonce, self.base_fee),
+                "pending/parked 경계가 다시 계산한 값과 다릅니다: {sender}"
+            );
+
+            total += queue.len();
+            if queue.local {
+                local += queue.len();
+            }
+            expected.add(*sender, queue.keys(self.base_fee));
+        }
+
+        assert_eq!(
+            self.total_txs, total,
+            "total_txs가 per_account 길이 합과 다릅니다"
+        );
+        assert_eq!(
+            self.local_txs, local,
+            "local_txs가 로컬 sender 보유 수와 다릅니다"
+        );
+        assert!(
+            local <= self.config.local_capacity,
+            "로컬 트랜잭션 {local}개가 local_capacity를 넘었습니다"
+        );
+        assert!(
+            total - local <= self.config.capacity,
+            "공개 트랜잭션 {}개가 capacity를 넘었습니다",
+            total - local
+        );
+
+        // 즉시 반영 설계라 stale 항목이 허용되지 않으므로, 모든 집합이 sender 큐에서 계산한 키와 정확히 같아야 합니다.
+        // global_queue의 모든 항목은 sender의 pending head여야 하고, 모든 pending head는 global_queue에 있어야 합니다.
+        assert_same_entries(
+            "global_queue(팁 상한)",
+            &self.global_queue.by_priority_fee,
+            &expected.global_queue.by_priority_fee,
+        );
+        assert_same_entries(
+            "global_queue(max_fee)",
+            &self.global_queue.by_max_fee,
+            &expected.global_queue.by_max_fee,
+        );
+        assert_same_entries(
+            "tails(팁 상한)",
+            &self.tails.by_priority_fee,
+            &expected.tails.by_priority_fee,
+        );
+        assert_same_entries(
+            "tails(max_fee)",
+            &self.tails.by_max_fee,
+            &expected.tails.by_max_fee,
+        );
+        assert_same_entries(
+            "pending_floors",
+            &self.pending_floors,
+            &expected.pending_floors,
+        );
+        assert_same_entries(
+            "parked_floors",
+            &self.parked_floors,
+            &expected.parked_floors,
+        );
+        assert_same_entries(
+            "head_thresholds",
+            &self.head_thresholds,
+            &expected.head_thresholds,
+        );
+        assert_same_entries(
+            "tail_thresholds",
+            &self.tail_thresholds,
+            &expected.tail_thresholds,
+        );
+        assert_same_entries(
+            "oldest_insertions",
+            &self.oldest_insertions,
+            &expected.oldest_insertions,
+        );
+    }
+}
+
+/// assert_invariants가 sender 큐에서 처음부터 다시 쌓는 인덱스 묶음입니다. 필드는 TxPool의 같은 이름 필드와 대응합니다.
+#[derive(Default)]
+struct TxPoolIndexes {
+    global_queue: CappedSet<HeadKey>,
+    tails: CappedSet<TailKey>,
+    pending_floors: BTreeSet<(u64, Pubkey)>,
+    parked_floors: BTreeSet<(u64, Pubkey)>,
+    head_thresholds: BTreeSet<(u64, Pubkey)>,
+    tail_thresholds: BTreeSet<(u64, Pubkey)>,
+    oldest_insertions: BTreeSet<(Instant, Pubkey)>,
+}
+
+impl TxPoolIndexes {
+    fn add(&mut self, sender: Pubkey, keys: SenderKeys) {
+        reindex(&mut self.pending_floors, sender, None, keys.pending_floor);
+        reindex(&mut self.parked_floors, sender, None, keys.parked_floor);
+        reindex(
+            &mut self.head_thresholds,
+            sender,
+            None,
+            keys.head.as_ref().map(|head| head.threshold),
+        );
+        reindex(
+            &mut self.tail_thresholds,
+            sender,
+            None,
+            keys.tail.as_ref().map(|tail| tail.threshold),
+        );
+        reindex(&mut self.oldest_insertions, sender, None, keys.oldest);
+        self.global_queue.replace(None, keys.head);
+        self.tails.replace(None, keys.tail);
+    }
+}
+
+/// 증분 유지한 집합과 다시 계산한 집합을 비교해, 남아 있는 stale 항목과 빠진 항목을 함께 보여 주며 panic합니다.
+fn assert_same_entries<T: Ord + Debug>(name: &str, actual: &BTreeSet<T>, expected: &BTreeSet<T>) {
+    if actual == expected {
+        return;
+    }
+    let stale: Vec<&T> = actual.difference(expected).collect();
+    let missing: Vec<&T> = expected.difference(actual).collect();
+    panic!("{name} 인덱스가 sender 큐와 다릅니다. stale: {stale:?}, missing: {missing:?}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TxPoolConfig;
+    use crate::tests::*;
+
+    #[test]
+    #[should_panic(expected = "global_queue")]
+    fn assert_invariants_catches_stale_head() {
+        // Given: alice 0이 pending head로 올라간 풀
+        let mut pool = TxPool::new(TxPoolConfig::default());
+        pool.insert(make_tx("alice", 0, 0, 10, "a")).unwrap();
+        pool.assert_invariants();
+        // When: 큐는 그대로 두고 global_queue 항목만 빼서 인덱스를 어긋나게 함
+        pool.global_queue.by_priority_fee.clear();
+        // Then: 검사기가 빠진 head를 잡아 panic
+        pool.assert_invariants();
+    }
+}
@@ -1,15 +1,64 @@
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap, VecDeque};
+mod best;
+mod handle;
+mod invariants;
+mod persist;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+mod validate;
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Debug;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+pub use best::BestTransactions;
+pub use handle::TxPoolHandle;
+pub use persist::{LoadReport, PersistError};
+pub use pubkey::Pubkey;
+pub use validate::{
+    AccountNonceProvider, InMemoryStateProvider, NoopValidator, StateProvider, StateValidator,
+    TransactionValidator,
+};
 
 /// 트랜잭션 삽입이 실패했을 때 호출자에게 사유를 돌려주기 위한 에러 타입입니다.
 #[derive(Debug)]
 pub enum TxInsertError {
-    /// 동일 sender에서 이미 존재하는 nonce 재삽입 예: `alice`가 nonce 3을 이미 올렸는데 다시 nonce 3을 제출한 경우.
-    DuplicateNonce { sender: String, nonce: u64 },
+    /// 동일 sender에서 이미 존재하는 nonce 재삽입 예: `alice`가 nonce 3을 이미 올렸는데 같은 hash의 nonce 3을 다시 제출한 경우.
+    /// hash가 다르면 교체(replace-by-fee) 요청으로 보고 `ReplacementUnderpriced` 규칙을 따릅니다.
+    DuplicateNonce { sender: Pubkey, nonce: u64 },
     /// 계정별 슬롯 상한 초과 시점 + `max_account_slots`가 0이라 신규 계정 자체가 허용되지 않는 상황도 포함합니다.
-    AccountLimitReached { sender: String },
-    /// capacity 도달 이후 축출까지 수행했으나 해당 삽입은 거부해야 함을 나타냅니다.
+    AccountLimitReached { sender: Pubkey },
+    /// capacity에 도달했고, 새 트랜잭션이 가장 나쁜 sender의 꼬리 트랜잭션보다 낫지 않아 아무것도 축출하지 않고 거부함을 나타냅니다.
+    /// 로컬 sender는 축출하지 않으므로 local_capacity에 도달하면 바로 이 에러입니다.
     PoolFull,
+    /// 이미 실행된 nonce 재제출 예: `alice`의 온체인 nonce가 5인데 nonce 3을 제출한 경우. `state_nonce`는 풀이 기대하는 다음 nonce입니다.
+    NonceTooLow {
+        sender: Pubkey,
+        nonce: u64,
+        state_nonce: u64,
+    },
+    /// 팁 상한이 validator가 요구하는 최소 팁에 못 미친 경우입니다. 블록 생산자가 담을 이유가 없는 트랜잭션을 막습니다.
+    Underpriced {
+        sender: Pubkey,
+        nonce: u64,
+        min_max_priority_fee_per_gas: u64,
+    },
+    /// 잔고 부족 예: 풀에 이미 있는 같은 sender 트랜잭션 비용과 새 트랜잭션 비용의 합이 `balance`를 넘는 경우.
+    /// `cost`는 그 합계이며, 교체 요청이면 밀려날 트랜잭션 비용은 빼고 계산합니다.
+    InsufficientFunds {
+        sender: Pubkey,
+        nonce: u64,
+        cost: u128,
+        balance: u128,
+    },
+    /// 같은 nonce 교체 시 수수료 인상 폭이 `price_bump`에 못 미친 경우입니다. 교체에 필요한 최소 max_fee·팁 상한을 함께 돌려줍니다.
+    ReplacementUnderpriced {
+        sender: Pubkey,
+        nonce: u64,
+        min_max_fee_per_gas: u64,
+        min_max_priority_fee_per_gas: u64,
+    },
 }
 
 /// 배치 팝 연산 결과입니다.
@@ -20,245 +69,1111 @@ pub enum PopResult {
     Empty,
 }
 
+/// 트랜잭션이 머무는 sub-pool 구분입니다. (Reth의 pending/basefee/queued sub-pool과 같은 의미)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubPool {
+    /// 온체인 nonce부터 빈틈 없이 이어지고 현재 base fee를 감당할 수 있어 바로 실행할 수 있는 트랜잭션입니다.
+    Pending,
+    /// nonce는 이어지지만 자신 또는 앞선 nonce의 max_fee가 base fee에 못 미쳐 base fee가 내려가기를 기다리는 트랜잭션입니다.
+    Parked,
+    /// 앞선 nonce가 비어 있어 빈틈이 채워질 때까지 기다리는 트랜잭션입니다.
+    Queued,
+}
+
+impl SubPool {
+    /// 용량이 찼을 때 남길 가치입니다. 실행에서 먼 queued가 가장 먼저, pending이 가장 나중에 밀려납니다.
+    fn retention_rank(self) -> u8 {
+        match self {
+            SubPool::Queued => 0,
+            SubPool::Parked => 1,
+            SubPool::Pending => 2,
+        }
+    }
+}
+
+/// 삽입 성공 결과입니다.
+#[derive(Debug)]
+pub struct InsertOutcome {
+    /// 새 트랜잭션이 들어간 sub-pool입니다.
+    pub sub_pool: SubPool,
+    /// 이번 삽입으로 queued·parked에서 pending으로 승격된 hash 목록이며 nonce 오름차순입니다.
+    pub promoted: Vec<String>,
+    /// 같은 nonce를 교체했다면 밀려난 기존 트랜잭션입니다.
+    pub replaced: Option<PendingTransaction>,
+    /// capacity를 맞추려고 새 트랜잭션 대신 풀에서 밀려난 hash 목록입니다.
+    pub evicted: Vec<String>,
+}
+
+/// 새 canonical 블록이 풀에 알려주는 변경분입니다.
+#[derive(Debug, Default)]
+pub struct CanonicalBlock {
+    /// 블록에 포함된 `(sender, nonce)` 목록입니다. 풀에 없는 트랜잭션이 섞여 있어도 됩니다.
+    pub mined: Vec<(Pubkey, u64)>,
+    /// 블록 실행 후 계정별 온체인 nonce입니다. 없는 sender는 mined의 최고 nonce + 1로 추정합니다.
+    pub account_nonces: HashMap<Pubkey, u64>,
+    /// 다음 블록에 적용될 base fee입니다. None이면 현재 값을 유지합니다.
+    pub base_fee: Option<u64>,
+}
+
+/// canonical 블록(또는 reorg) 반영 결과입니다.
+#[derive(Debug, Default)]
+pub struct CanonicalUpdate {
+    /// 블록에 포함되어 풀에서 빠진 트랜잭션입니다.
+    pub mined: Vec<PendingTransaction>,
+    /// 블록에 포함되지 않았지만 nonce가 state nonce 아래로 밀려 더 이상 실행될 수 없어 버린 트랜잭션입니다.
+    /// reorg 재삽입에 실패한 트랜잭션(같은 nonce 자리를 다른 트랜잭션이 차지한 경우 포함)도 여기에 담습니다.
+    pub discarded: Vec<PendingTransaction>,
+    /// state nonce나 base fee가 바뀌면서 pending으로 승격된 hash 목록입니다.
+    pub promoted: Vec<String>,
+    /// reorg로 되돌려진 블록에서 다시 풀에 들어온 hash 목록입니다.
+    pub reinjected: Vec<String>,
+}
+
+/// 풀 구성이 바뀔 때마다 구독자에게 보내는 트랜잭션 단위 이벤트입니다.
+/// Added로 들어온 hash는 정확히 한 번 Replaced·Evicted·Expired·Mined·Discarded 중 하나로 빠지므로,
+/// 이벤트만 따라가도 풀이 보유한 hash 집합을 재구성할 수 있습니다. (pop_batch로 꺼낸 트랜잭션은 호출자가 이미 받았으므로 제외합니다)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PoolEvent {
+    /// 새 트랜잭션이 풀에 들어왔습니다. sub_pool은 삽입 직후 위치입니다.
+    Added { hash: String, sub_pool: SubPool },
+    /// 같은 nonce 교체로 `hash`가 빠졌습니다. 바로 뒤에 `replaced_by`의 Added가 이어집니다.
+    Replaced { hash: String, replaced_by: String },
+    /// capacity를 맞추려고 축출되었습니다. 바로 뒤에 자리를 차지한 트랜잭션의 Added가 이어집니다.
+    Evicted { hash: String },
+    /// `max_age`가 지나 prune_expired에서 빠졌습니다.
+    Expired { hash: String },
+    /// canonical 블록에 포함되었습니다.
+    Mined { hash: String },
+    /// 실행될 수 없게 되어 버렸습니다. (state nonce 아래로 밀림, 앞선 nonce 만료, reorg 재삽입 실패)
+    Discarded { hash: String },
+}
+
+/// `prune_expired` 결과입니다. 두 목록 모두 sender별 nonce 오름차순입니다.
+#[derive(Debug, Default)]
+pub struct PruneOutcome {
+    /// 삽입 후 `max_age`가 지나 만료된 트랜잭션입니다.
+    pub expired: Vec<PendingTransaction>,
+    /// 자신은 만료되지 않았지만 앞선 nonce가 만료되어 실행될 수 없게 된 같은 sender의 후속 트랜잭션입니다.
+    pub dependents: Vec<PendingTransaction>,
+}
+
+/// 트랜잭션 삽입 시각을 찍는 시계입니다. 테스트에서는 직접 움직이는 시계를 넣어 TTL 만료를 재현합니다.
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// 기본 시계로 `Instant::now()`를 그대로 씁니다.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// `|| instant` 형태의 클로저도 시계로 쓸 수 있습니다.
+impl<F: Fn() -> Instant> Clock for F {
+    fn now(&self) -> Instant {
+        self()
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PendingTransaction {
-    /// 트랜잭션 식별자(고유해야 함)로 lazy eviction 비교와 중복 감지를 모두 여기서 수행합니다.
+    /// 트랜잭션 식별자(고유해야 함)로 중복 감지와 승격·축출 결과 보고를 모두 여기서 수행합니다.
     pub hash: String,
     /// 서명 계정 ID, 동일 sender 묶음은 TxPool::per_account 하나만 사용합니다.
-    pub sender: String,
+    pub sender: Pubkey,
     /// 계정별 실행 순서를 보장하는 nonce, 같은 sender에서 중복을 허용하지 않습니다.
     pub nonce: u64,
-    /// 전역 우선순위 보조 지표로 쓰이는 gas_price, 음수 불가이므로 부호 없는 정수만 저장합니다.
-    pub gas_price: u64,
-    /// reputation/tip 등 가중치, 가스 가격과 독립적으로 비교합니다.
+    /// 가스당 최대 지불액(base fee + 팁)입니다. 레거시 트랜잭션은 gas_price를 그대로 넣습니다.
+    pub max_fee_per_gas: u64,
+    /// 가스당 블록 생산자에게 줄 팁 상한입니다. 레거시 트랜잭션은 gas_price와 같은 값을 넣습니다.
+    pub max_priority_fee_per_gas: u64,
+    /// reputation 등 외부 가중치로, effective tip이 같을 때만 비교합니다.
     pub priority: u128,
+    /// 가스 한도로, max_fee와 곱해 최대 수수료 비용을 계산합니다.
+    pub gas_limit: u64,
+    /// 수신자에게 보내는 금액입니다.
+    pub value: u128,
 }
 
 impl PendingTransaction {
-    /// priority 우선 비교, 동률 시 gas_price 비교를 수행하도록 `(priority, gas_price)` 튜플을 반환합니다.
-    pub fn priority_key(&self) -> (u128, u64) {
-        // priority는 reputation 개념이라 더 큰 값이 우선이라면 첫 번째 요소에서 비교합니다.
-        // gas_price는 동률일 때 보조 지표이므로 두 번째 요소로 비교합니다.
-        (self.priority, self.gas_price)
+    /// 잔고에서 빠질 수 있는 최대 금액 `max_fee * gas_limit + value`입니다.
+    pub fn cost(&self) -> u128 {
+        u128::from(self.max_fee_per_gas)
+            .saturating_mul(u128::from(self.gas_limit))
+            .saturating_add(self.value)
+    }
+
+    /// base fee에서 실제로 받는 팁 `min(팁 상한, max_fee - base_fee)`입니다. max_fee가 base fee에 못 미치면 None입니다.
+    pub fn effective_tip(&self, base_fee: u64) -> Option<u64> {
+        let headroom = self.max_fee_per_gas.checked_sub(base_fee)?;
+        Some(headroom.min(self.max_priority_fee_per_gas))
+    }
+
+    /// effective tip 우선 비교, 동률 시 priority 비교를 수행하도록 `(effective tip, priority)` 튜플을 반환합니다.
+    pub fn priority_key(&self, base_fee: u64) -> (u64, u128) {
+        // base fee 미달 트랜잭션은 pending에 오르지 않지만, 비교가 필요하면 팁 0으로 취급합니다.
+        (self.effective_tip(base_fee).unwrap_or(0), self.priority)
+    }
+
+    /// base fee가 이 값 이하이면 팁 상한이 그대로 effective tip이고, 넘어서면 `max_fee - base_fee`로 줄어듭니다.
+    fn tip_threshold(&self) -> u64 {
+        self.max_fee_per_gas
+            .saturating_sub(self.max_priority_fee_per_gas)
+    }
+
+    /// 현재 base fee에서 effective tip을 결정하는 쪽이 어느 상한인지 돌려줍니다.
+    fn tip_cap(&self, base_fee: u64) -> TipCap {
+        if self.tip_threshold() >= base_fee {
+            TipCap::PriorityFee
+        } else {
+            TipCap::MaxFee
+        }
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct QueuedTx {
-    /// BinaryHeap 비교용 `(priority, gas_price)` 캐시이며 항상 `tx.priority_key()`와 동기화합니다.
-    pub key: (u128, u64),
-    /// 실제 대기 트랜잭션이며 내부에서 clone을 유지하고 외부에서 소유권을 공유하지 않습니다.
-    pub tx: PendingTransaction,
+/// effective tip을 결정하는 상한입니다. head·꼬리가 CappedSet의 어느 집합에 정렬되는지 정합니다.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TipCap {
+    /// effective tip = max_priority_fee_per_gas. base fee와 무관합니다.
+    PriorityFee,
+    /// effective tip = max_fee_per_gas - base_fee. base fee가 바뀌어도 이 그룹 안의 순서는 max_fee 순서 그대로입니다.
+    MaxFee,
+}
+
+impl TipCap {
+    /// 이 상한 쪽 집합에서 정렬에 쓰는 base fee와 무관한 수수료입니다.
+    /// 팁 상한이 max_fee보다 크면 base fee 0에서도 max_fee까지만 받으므로 max_fee로 자릅니다.
+    fn sort_fee(self, tx: &PendingTransaction) -> u64 {
+        match self {
+            TipCap::PriorityFee => tx.max_priority_fee_per_gas.min(tx.max_fee_per_gas),
+            TipCap::MaxFee => tx.max_fee_per_gas,
+        }
+    }
+
+    /// `sort_fee`를 현재 base fee의 effective tip으로 되돌립니다. base fee 미달은 0입니다.
+    fn effective_fee(self, sort_fee: u64, base_fee: u64) -> u64 {
+        match self {
+            TipCap::PriorityFee => sort_fee,
+            TipCap::MaxFee => sort_fee.saturating_sub(base_fee),
+        }
+    }
+}
+
+/// pending head 정렬 키입니다. 필드 순서대로 비교하며 집합의 가장 큰 항목이 가장 먼저 나갈 head입니다.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct HeadKey {
+    /// 팁 상한 집합은 max_priority_fee, max_fee 집합은 max_fee입니다. 둘 다 base fee와 무관한 값이라
+    /// base fee가 바뀌어도 집합 안의 순서가 유지됩니다.
+    fee: u64,
+    priority: u128,
+    /// 수수료가 모두 동일할 때는 더 낮은 nonce(= 먼저 실행 가능한 항목)를 우선시합니다.
+    nonce: Reverse<u64>,
+    sender: Pubkey,
+}
+
+impl HeadKey {
+    fn new(tx: &PendingTransaction, cap: TipCap) -> Self {
+        Self {
+            fee: cap.sort_fee(tx),
+            priority: tx.priority,
+            nonce: Reverse(tx.nonce),
+            sender: tx.sender,
+        }
+    }
+
+    /// `cap` 집합에 있는 이 head의 현재 base fee 기준 실행 순위입니다.
+    fn best_rank(&self, cap: TipCap, base_fee: u64) -> BestRank {
+        (
+            cap.effective_fee(self.fee, base_fee),
+            self.priority,
+            self.nonce,
+            self.sender,
+        )
+    }
+}
+
+/// 실행 순서 비교용 `(effective tip, priority, Reverse(nonce), sender)` 순위이며 클수록 먼저 나갑니다.
+type BestRank = (u64, u128, Reverse<u64>, Pubkey);
+
+fn best_rank(tx: &PendingTransaction, base_fee: u64) -> BestRank {
+    let (effective_tip, priority) = tx.priority_key(base_fee);
+    (effective_tip, priority, Reverse(tx.nonce), tx.sender)
+}
+
+/// 축출 후보(sender별 최고 nonce) 정렬 키입니다. 필드 순서대로 비교하며 집합의 가장 작은 항목이 가장 먼저 밀려날 꼬리입니다.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct TailKey {
+    /// `SubPool::retention_rank`로, queued 꼬리가 pending 꼬리보다 먼저 밀려납니다.
+    retention: u8,
+    /// HeadKey::fee와 같은 규칙의 정렬 수수료입니다.
+    fee: u64,
+    /// effective tip이 같을 때 base fee 상승을 더 오래 버티는 쪽을 남기도록 max_fee를 비교합니다.
+    max_fee: u64,
+    priority: u128,
+    sender: Pubkey,
+    nonce: u64,
 }
 
-impl QueuedTx {
-    /// front 트랜잭션을 우선순위 큐에 넣기 전에 key 캐시를 만들어 둡니다.
-    pub fn new(tx: PendingTransaction) -> Self {
-        // priority_key 호출은 필수 불변식(캐시 동기화)을 지키기 위해 new 시점에만 수행합니다.
-        let key = tx.priority_key();
-        Self { key, tx }
+impl TailKey {
+    fn new(tx: &PendingTransaction, sub_pool: SubPool, cap: TipCap) -> Self {
+        Self {
+            retention: sub_pool.retention_rank(),
+            fee: cap.sort_fee(tx),
+            max_fee: tx.max_fee_per_gas,
+            priority: tx.priority,
+            sender: tx.sender,
+            nonce: tx.nonce,
+        }
+    }
+
+    /// 현재 base fee에서의 축출 순위입니다. `TxPool::eviction_rank`와 같은 값을 냅니다.
+    fn rank(&self, cap: TipCap, base_fee: u64) -> EvictionRank {
+        (
+            self.retention,
+            cap.effective_fee(self.fee, base_fee),
+            self.max_fee,
+            self.priority,
+        )
+    }
+}
+
+/// 축출 비교용 `(retention_rank, effective tip, max_fee, priority)` 순위이며 작을수록 먼저 밀려납니다.
+type EvictionRank = (u8, u64, u64, u128);
+
+/// 정렬 집합에 올린 키와 그 키가 들어간 집합, 집합 구분이 바뀌는 base fee 경계(tip_threshold)를 함께 기억합니다.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Indexed<K> {
+    key: K,
+    cap: TipCap,
+    threshold: u64,
+}
+
+impl<K> Indexed<K> {
+    fn new(tx: &PendingTransaction, base_fee: u64, key: impl FnOnce(TipCap) -> K) -> Self {
+        let cap = tx.tip_cap(base_fee);
+        Self {
+            key: key(cap),
+            cap,
+            threshold: tx.tip_threshold(),
+        }
     }
 }
 
-impl Ord for QueuedTx {
-    fn cmp(&self, other: &Self) -> Ordering {
-        // Reth는 max-heap을 사용해 가장 높은 우선순위를 먼저 스케줄합니다.
-        let by_priority = other.key.cmp(&self.key);
-        // priority, gas_price가 모두 동일할 때는 더 낮은 nonce(= 먼저 실행 가능한 항목)를 우선시합니다.
-        if by_priority == Ordering::Equal {
-            return other.tx.nonce.cmp(&self.tx.nonce);
+/// effective tip 순으로 정렬해야 하는 키를 두 BTreeSet에 나눠 담습니다.
+/// effective tip = min(팁 상한, max_fee - base_fee)라서 항목마다 어느 쪽 상한에 걸리는지에 따라 나눠 담으면
+/// 각 집합 안의 순서는 base fee와 무관해집니다. base fee가 바뀌면 경계를 넘은 항목만 반대쪽 집합으로 옮기고,
+/// 삽입·삭제를 즉시 반영하므로 stale 항목이 남지 않아 최고·최저 조회와 갱신이 모두 O(log n)입니다.
+struct CappedSet<K> {
+    by_priority_fee: BTreeSet<K>,
+    by_max_fee: BTreeSet<K>,
+}
+
+impl<K> Default for CappedSet<K> {
+    fn default() -> Self {
+        Self {
+            by_priority_fee: BTreeSet::new(),
+            by_max_fee: BTreeSet::new(),
         }
-        by_priority.reverse()
     }
 }
 
-impl PartialOrd for QueuedTx {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        // BinaryHeap 요구사항에 맞춰 전체 순서를 정의하므로 그대로 cmp만 위임합니다.
-        Some(self.cmp(other))
+impl<K: Ord> CappedSet<K> {
+    fn set(&self, cap: TipCap) -> &BTreeSet<K> {
+        match cap {
+            TipCap::PriorityFee => &self.by_priority_fee,
+            TipCap::MaxFee => &self.by_max_fee,
+        }
+    }
+
+    fn set_mut(&mut self, cap: TipCap) -> &mut BTreeSet<K> {
+        match cap {
+            TipCap::PriorityFee => &mut self.by_priority_fee,
+            TipCap::MaxFee => &mut self.by_max_fee,
+        }
+    }
+
+    /// sender 하나의 항목을 before → after로 옮깁니다. 같으면 아무것도 하지 않습니다.
+    fn replace(&mut self, before: Option<Indexed<K>>, after: Option<Indexed<K>>) {
+        if before == after {
+            return;
+        }
+        if let Some(old) = before {
+            self.set_mut(old.cap).remove(&old.key);
+        }
+        if let Some(new) = after {
+            self.set_mut(new.cap).insert(new.key);
+        }
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.by_priority_fee.len() + self.by_max_fee.len()
+    }
+
+    #[cfg(test)]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 }
 
+#[derive(Clone)]
 pub struct TxPoolConfig {
-    /// 풀 전체 허용 개수(capacity)이며 0 이상, 음수는 불가능합니다.
+    /// 공개 트랜잭션 허용 개수(capacity)이며 0 이상, 음수는 불가능합니다. 로컬 sender 트랜잭션은 세지 않습니다.
+    /// TxPoolHandle에서는 shard 하나의 한도이며, 핸들 전체 한도는 `shards × capacity`입니다. (local_capacity도 같습니다)
     pub capacity: usize,
     /// 계정별 허용 개수(max_account_slots)이며 0 이상, 0이면 신규 계정을 추가할 수 없습니다.
     pub max_account_slots: usize,
+    /// 같은 nonce 교체에 필요한 최소 수수료 인상률(%)이며 max_fee와 팁 상한 모두에 적용합니다.
+    pub price_bump: u64,
+    /// 트랜잭션이 풀에 머물 수 있는 최대 시간입니다. 이보다 오래된 트랜잭션은 `prune_expired`에서 빠집니다.
+    /// 교체된 트랜잭션은 교체 시점부터 다시 잽니다.
+    pub max_age: Duration,
+    /// 운영자가 직접 제출하는 로컬 sender 목록입니다. (keeper, liquidator 등)
+    /// 이 sender의 트랜잭션은 max_account_slots·축출·TTL 만료에서 제외되고 local_capacity만 씁니다.
+    pub local_senders: HashSet<Pubkey>,
+    /// 로컬 sender 전용으로 예약한 허용 개수입니다. 공개 capacity와 따로 세므로 스팸이 이 자리를 차지할 수 없습니다.
+    pub local_capacity: usize,
+}
+
+impl Default for TxPoolConfig {
+    /// Reth 기본값과 같은 10% 인상률·3시간 수명을 쓰고, 용량은 학습용으로 작게 잡습니다.
+    fn default() -> Self {
+        Self {
+            capacity: 10_000,
+            max_account_slots: 16,
+            price_bump: 10,
+            max_age: Duration::from_secs(3 * 60 * 60),
+            local_senders: HashSet::new(),
+            local_capacity: 1_000,
+        }
+    }
+}
+
+/// `old`에 `bump`%를 더한 최소 교체 가격입니다. 곱셈 오버플로를 피하려고 몫과 나머지를 나눠 계산합니다.
+fn bumped_price(old: u64, bump: u64) -> u64 {
+    let extra = (old / 100)
+        .saturating_mul(bump)
+        .saturating_add(old % 100 * bump.min(u64::MAX / 100) / 100);
+    old.saturating_add(extra)
+}
+
+/// sender 하나의 트랜잭션을 nonce 순으로 보관하고, 앞에서부터 pending → parked → queued로 나눕니다.
+#[derive(Default)]
+struct SenderQueue {
+    /// nonce → 트랜잭션이며 BTreeMap이라 항상 nonce 오름차순입니다.
+    txs: BTreeMap<u64, PendingTransaction>,
+    /// 로컬 sender 큐이면 true입니다. 꼬리·삽입 시각을 인덱스에 올리지 않아 축출·만료 대상에서 빠집니다.
+    local: bool,
+    /// nonce → 삽입 시각이며 txs와 항상 같은 nonce 집합을 가집니다. 아래 insert/remove/split 메서드로만 바꿉니다.
+    inserted_at: BTreeMap<u64, Instant>,
+    /// 앞에서부터 pending 개수입니다. state nonce부터 빈틈 없이 이어지고 max_fee가 모두 base fee 이상인 구간입니다.
+    pending: usize,
+    /// pending 바로 뒤의 parked 개수입니다. nonce는 이어지지만 base fee 미달 트랜잭션에서 끊긴 구간입니다.
+    parked: usize,
+}
+
+impl SenderQueue {
+    /// pending·parked·queued를 합친 보유 수로, max_account_slots 비교에 씁니다.
+    fn len(&self) -> usize {
+        self.txs.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.txs.is_empty()
+    }
+
+    fn queued(&self) -> usize {
+        self.txs.len() - self.pending - self.parked
+    }
+
+    /// nonce 자리에 넣고 삽입 시각을 기록합니다. 같은 nonce가 있었다면 밀려난 트랜잭션을 돌려줍니다.
+    fn insert(&mut self, tx: PendingTransaction, now: Instant) -> Option<PendingTransaction> {
+        self.inserted_at.insert(tx.nonce, now);
+        self.txs.insert(tx.nonce, tx)
+    }
+
+    fn remove(&mut self, nonce: u64) -> Option<PendingTransaction> {
+        self.inserted_at.remove(&nonce);
+        self.txs.remove(&nonce)
+    }
+
+    /// `nonce` 미만 트랜잭션을 모두 떼어 nonce 오름차순으로 돌려줍니다.
+    fn split_below(&mut self, nonce: u64) -> BTreeMap<u64, PendingTransaction> {
+        let kept = self.inserted_at.split_off(&nonce);
+        self.inserted_at = kept;
+        let kept = self.txs.split_off(&nonce);
+        std::mem::replace(&mut self.txs, kept)
+    }
+
+    /// `nonce` 이상 트랜잭션을 모두 떼어 삽입 시각과 함께 nonce 오름차순으로 돌려줍니다.
+    fn split_from(&mut self, nonce: u64) -> Vec<(PendingTransaction, Instant)> {
+        let inserted_at = self.inserted_at.split_off(&nonce);
+        self.txs
+            .split_off(&nonce)
+            .into_values()
+            .zip(inserted_at.into_values())
+            .collect()
+    }
+
+    /// `cutoff`보다 먼저 들어온 트랜잭션 중 가장 낮은 nonce입니다. 여기서부터 뒤는 모두 실행될 수 없게 됩니다.
+    fn first_expired(&self, cutoff: Instant) -> Option<u64> {
+        self.inserted_at
+            .iter()
+            .find(|&(_, &inserted_at)| inserted_at < cutoff)
+            .map(|(&nonce, _)| nonce)
+    }
+
+    /// 모든 트랜잭션을 nonce 오름차순으로 sub-pool과 함께 돌려줍니다.
+    fn txs_with_sub_pool(&self) -> impl Iterator<Item = (SubPool, &PendingTransaction)> {
+        self.txs.values().enumerate().map(|(position, tx)| {
+            let sub_pool = if position < self.pending {
+                SubPool::Pending
+            } else if position < self.pending + self.parked {
+                SubPool::Parked
+            } else {
+                SubPool::Queued
+            };
+            (sub_pool, tx)
+        })
+    }
+
+    fn pending_txs(&self) -> impl Iterator<Item = &PendingTransaction> {
+        self.txs.values().take(self.pending)
+    }
+
+    /// global_queue에 올라가는 실행 가능한 첫 트랜잭션입니다.
+    fn head(&self) -> Option<&PendingTransaction> {
+        self.pending_txs().next()
+    }
+
+    fn parked_head(&self) -> Option<&PendingTransaction> {
+        self.txs
+            .values()
+            .nth(self.pending)
+            .filter(|_| self.parked > 0)
+    }
+
+    /// 가장 높은 nonce 트랜잭션과 그 sub-pool입니다. 꼬리를 빼도 남은 nonce 사슬은 끊기지 않습니다.
+    fn tail(&self) -> Option<(&PendingTransaction, SubPool)> {
+        let (_, tx) = self.txs.last_key_value()?;
+        let sub_pool = if self.queued() > 0 {
+            SubPool::Queued
+        } else if self.parked > 0 {
+            SubPool::Parked
+        } else {
+            SubPool::Pending
+        };
+        Some((tx, sub_pool))
+    }
+
+    /// 아직 없는 nonce의 트랜잭션이 들어온다면 어느 sub-pool에 놓일지 계산합니다.
+    fn prospective_sub_pool(
+        &self,
+        tx: &PendingTransaction,
+        state_nonce: u64,
+        base_fee: u64,
+    ) -> SubPool {
+        // pending·parked는 state nonce부터 빈틈 없이 이어지므로 그 끝 바로 다음 nonce만 사슬에 붙습니다.
+        let ready_end = state_nonce + (self.pending + self.parked) as u64;
+        if tx.nonce != ready_end {
+            SubPool::Queued
+        } else if self.parked == 0 && tx.max_fee_per_gas >= base_fee {
+            SubPool::Pending
+        } else {
+            SubPool::Parked
+        }
+    }
+
+    fn sub_pool(&self, nonce: u64) -> Option<SubPool> {
+        if !self.txs.contains_key(&nonce) {
+            return None;
+        }
+        let position = self.txs.range(..nonce).count();
+        Some(if position < self.pending {
+            SubPool::Pending
+        } else if position < self.pending + self.parked {
+            SubPool::Parked
+        } else {
+            SubPool::Queued
+        })
+    }
+
+    /// state nonce와 base fee로 pending/parked 경계를 다시 계산합니다.
+    fn settle(&mut self, state_nonce: u64, base_fee: u64) {
+        (self.pending, self.parked) = self.boundaries(state_nonce, base_fee);
+    }
+
+    /// state nonce와 base fee 기준의 `(pending 개수, parked 개수)`입니다.
+    /// 보유 수가 max_account_slots 이하라 앞에서부터 선형으로 훑어도 충분합니다.
+    fn boundaries(&self, state_nonce: u64, base_fee: u64) -> (usize, usize) {
+        let (mut pending, mut parked) = (0, 0);
+        for (expected, (&nonce, tx)) in (state_nonce..).zip(&self.txs) {
+            if nonce != expected {
+                break;
+            }
+            // 한 번 base fee 미달을 만나면 그 뒤 nonce는 수수료와 상관없이 실행할 수 없습니다.
+            if parked == 0 && tx.max_fee_per_gas >= base_fee {
+                pending += 1;
+            } else {
+                parked += 1;
+            }
+        }
+        (pending, parked)
+    }
+
+    /// 풀 인덱스에 올린 키를 찍어 둡니다. base_fee는 스냅숏 시점의 값이어야 합니다.
+    fn keys(&self, base_fee: u64) -> SenderKeys {
+        SenderKeys {
+            pending_floor: self.pending_txs().map(|tx| tx.max_fee_per_gas).min(),
+            parked_floor: self.parked_head().map(|tx| tx.max_fee_per_gas),
+            head: self
+                .head()
+                .map(|tx| Indexed::new(tx, base_fee, |cap| HeadKey::new(tx, cap))),
+            tail: self.tail().filter(|_| !self.local).map(|(tx, sub_pool)| {
+                Indexed::new(tx, base_fee, |cap| TailKey::new(tx, sub_pool, cap))
+            }),
+            pending_hashes: self.pending_txs().map(|tx| tx.hash.clone()).collect(),
+            oldest: self
+                .inserted_at
+                .values()
+                .min()
+                .copied()
+                .filter(|_| !self.local),
+            local_len: if self.local { self.len() } else { 0 },
+        }
+    }
+}
+
+/// sender 하나가 풀 인덱스에 올린 키 묶음입니다. 변경 전후를 비교해 인덱스와 정렬 집합을 갱신합니다.
+#[derive(Default)]
+struct SenderKeys {
+    /// pending 중 가장 낮은 max_fee입니다. (pending_floors 키)
+    pending_floor: Option<u64>,
+    /// parked 첫 트랜잭션의 max_fee입니다. (parked_floors 키)
+    parked_floor: Option<u64>,
+    /// global_queue에 올린 head 키입니다. threshold는 head_thresholds 키입니다.
+    head: Option<Indexed<HeadKey>>,
+    /// tails에 올린 꼬리 키입니다. threshold는 tail_thresholds 키입니다.
+    tail: Option<Indexed<TailKey>>,
+    /// 승격 판별용 pending hash 목록입니다.
+    pending_hashes: HashSet<String>,
+    /// 가장 이른 삽입 시각입니다. (oldest_insertions 키)
+    oldest: Option<Instant>,
+    /// 로컬 sender이면 보유 수, 아니면 0입니다. (local_txs 합산분)
+    local_len: usize,
+}
+
+/// `(값, sender)` 인덱스에서 sender의 키를 before → after로 옮깁니다.
+fn reindex<T: Ord>(
+    index: &mut BTreeSet<(T, Pubkey)>,
+    sender: Pubkey,
+    before: Option<T>,
+    after: Option<T>,
+) {
+    if before == after {
+        return;
+    }
+    if let Some(value) = before {
+        index.remove(&(value, sender));
+    }
+    if let Some(value) = after {
+        index.insert((value, sender));
+    }
 }
 
 pub struct TxPool {
     /// 현재 설정으로, 생성 이후에는 변경하지 않는 불변 데이터를 저장합니다.
     config: TxPoolConfig,
-    /// sender → VecDeque 매핑이며 각 큐는 항상 nonce 오름차순으로 정렬되어야 합니다.
-    per_account: HashMap<String, VecDeque<PendingTransaction>>,
-    /// BinaryHeap<QueuedTx> 구조로 각 계정의 front만 유지합니다.
-    global_queue: BinaryHeap<QueuedTx>,
+    /// sender → SenderQueue 매핑이며 각 큐는 nonce 오름차순으로 pending/parked/queued 경계를 유지합니다.
+    per_account: HashMap<Pubkey, SenderQueue>,
+    /// 각 계정의 pending head만 유지하며, effective tip을 결정하는 상한별로 두 집합에 나눠 담습니다.
+    global_queue: CappedSet<HeadKey>,
+    /// 각 계정의 꼬리(최고 nonce) 트랜잭션만 축출 순위로 유지합니다. 가장 작은 항목이 축출 1순위입니다.
+    tails: CappedSet<TailKey>,
     /// 풀에서 보유 중인 트랜잭션 수로, per_account 전체 길이 합과 동일해야 합니다.
     total_txs: usize,
+    /// total_txs 중 로컬 sender 트랜잭션 수입니다. 공개 트랜잭션 수는 `total_txs - local_txs`입니다.
+    local_txs: usize,
+    /// 온체인 nonce 조회기이며 처음 보는 sender의 state nonce를 정할 때만 호출합니다.
+    nonce_provider: Box<dyn AccountNonceProvider + Send + Sync>,
+    /// sender → 풀이 기대하는 다음 실행 nonce 캐시입니다. pop_batch로 내보낸 만큼 앞당겨지며,
+    /// sender가 풀에서 사라져도 남겨 두어 이미 내보낸 nonce가 다시 pending에 들어오지 않게 합니다.
+    state_nonces: HashMap<Pubkey, u64>,
+    /// 다음 블록의 base fee로, pending/parked 경계와 effective tip 계산의 기준입니다.
+    base_fee: u64,
+    /// `(pending 중 최소 max_fee, sender)`이며 base fee가 오를 때 parked로 내려갈 sender만 찾는 데 씁니다.
+    pending_floors: BTreeSet<(u64, Pubkey)>,
+    /// `(parked 첫 트랜잭션의 max_fee, sender)`이며 base fee가 내릴 때 pending으로 올라올 sender만 찾는 데 씁니다.
+    parked_floors: BTreeSet<(u64, Pubkey)>,
+    /// `(head의 tip_threshold, sender)`이며 base fee 변경으로 집합 구분이 바뀌는 head만 찾는 데 씁니다.
+    head_thresholds: BTreeSet<(u64, Pubkey)>,
+    /// `(꼬리의 tip_threshold, sender)`이며 base fee 변경으로 집합 구분이 바뀌는 꼬리만 찾는 데 씁니다.
+    tail_thresholds: BTreeSet<(u64, Pubkey)>,
+    /// 삽입 시각을 찍는 시계입니다.
+    clock: Box<dyn Clock + Send + Sync>,
+    /// `(sender의 가장 이른 삽입 시각, sender)`이며 prune_expired가 만료된 sender만 찾는 데 씁니다.
+    oldest_insertions: BTreeSet<(Instant, Pubkey)>,
+    /// insert가 풀 규칙보다 먼저 호출하는 트랜잭션 검사기입니다.
+    validator: Box<dyn TransactionValidator + Send + Sync>,
+    /// subscribe로 등록한 이벤트 구독자입니다. 수신 측이 닫히면 다음 전송 때 빠집니다.
+    listeners: Vec<mpsc::Sender<PoolEvent>>,
 }
 
 // 메서드 명세 요약:
 // | 메서드 | 성공 조건 | 실패 조건 | 상태 변화 | 후속 처리 |
 // | --- | --- | --- | --- | --- |
-// | new | per_account/global_queue 비우고 total_txs=0 | 없음 | 모든 필드 초기화 | 없음 |
-// | insert | 계정 큐 유지 + total_txs 증가 | DuplicateNonce, AccountLimitReached, PoolFull | nonce 오름차순 삽입, front 변경 시 global_queue 갱신 | 필요 시 lazy eviction 트리거 |
-// | pop_batch | drained 길이 ≤ limit, priority 내림차순 | 반환 Empty | per_account/global_queue에서 제거, total_txs 감소 | 동일 sender 후속 nonce front 재등록 |
-// | evict_lowest_priority | 최소 우선순위 1건 제거 | 유효 트랜잭션 없으면 조용히 종료 | per_account에서 제거, total_txs 감소 | front 변경 시 global_queue 재등록 |
+// | new | per_account/global_queue/tails 비우고 total_txs=0 | 없음 | 모든 필드 초기화 | 없음 |
+// | insert | validator 통과 + 계정 큐 유지 + total_txs 증가 | Underpriced, InsufficientFunds, NonceTooLow, DuplicateNonce, ReplacementUnderpriced, AccountLimitReached, PoolFull | nonce 순 삽입, 같은 nonce는 price_bump 이상이면 제자리 교체, 로컬 sender는 슬롯·축출 없이 local_capacity만 확인 | settle로 sub-pool 경계 재계산, head·꼬리 변경 시 global_queue·tails 갱신, Evicted·Replaced·Added 이벤트 |
+// | best_transactions | pop_batch와 같은 순서로 pending을 하나씩 반환 | 없음 | 없음 (풀을 빌리기만 함) | mark_invalid로 sender의 해당 nonce 이후 건너뜀 |
+// | pop_batch | drained 길이 ≤ limit, priority 내림차순, pending만 반환 | 반환 Empty | per_account/global_queue에서 제거, total_txs 감소, state nonce 전진 | 동일 sender 후속 nonce head 등록 |
+// | worst_tail | tails 앞쪽에서 새 트랜잭션보다 나쁜 최악 꼬리 1건 선택 (O(log n)) | 없으면 None → PoolFull | 없음 | insert가 remove_tx로 제거 후 evicted에 기록 |
+// | on_canonical_block | 블록 변경분 반영 | 없음 | mined·stale 제거, total_txs 감소, state nonce·base fee 갱신 | 승격, 바뀐 pending head 등록, Mined·Discarded 이벤트 |
+// | on_reorg | 새 블록 반영 후 되돌린 tx 재삽입 | 재삽입 실패 건은 discarded | on_canonical_block + insert | insert와 동일 |
+// | set_base_fee | 경계를 넘은 sender만 재계산 | 없음 | pending ↔ parked 이동 | 집합 구분이 바뀐 head·꼬리만 반대 집합으로 이동 |
+// | subscribe | 새 수신기 등록 | 없음 | listeners 추가 | 이후 모든 변경이 PoolEvent로 전달, 닫힌 수신기는 전송 시 제거 |
+// | save_to / load_from | 버전 헤더 + 줄별 체크섬 스냅숏 저장·복원 (state nonce 포함) | 파일 I/O 실패, 헤더·버전 불일치 | 복원은 insert 경로로 재삽입 | 손상 줄은 corrupted, 검증 실패는 rejected로 보고 |
+// | prune_expired | max_age를 넘긴 트랜잭션 제거 | 없음 | 만료 nonce부터 같은 sender 후속 nonce까지 제거, total_txs 감소 | expired·dependents 보고, head·꼬리 갱신, Expired·Discarded 이벤트 |
+// | assert_invariants | 카운터·인덱스·정렬 집합이 sender 큐에서 다시 계산한 값과 같음 | 어긋나면 panic (테스트·디버깅 전용) | 없음 | 없음 |
 impl TxPool {
-    /// config만 받아 초기 상태를 구성합니다.
+    /// config만 받아 초기 상태를 구성합니다. 모든 계정의 온체인 nonce를 0으로 간주합니다.
     pub fn new(config: TxPoolConfig) -> Self {
-        // 초기화 시 빈 HashMap/Heap/카운터를 만들어 표의 성공 조건을 만족시킵니다.
+        Self::with_nonce_provider(config, HashMap::new())
+    }
+
+    /// 온체인 nonce 조회기를 함께 받아 초기 상태를 구성합니다. base fee는 0에서 시작합니다.
+    pub fn with_nonce_provider(
+        config: TxPoolConfig,
+        nonce_provider: impl AccountNonceProvider + Send + Sync + 'static,
+    ) -> Self {
+        // 초기화 시 빈 HashMap/정렬 집합/카운터를 만들어 표의 성공 조건을 만족시킵니다.
         Self {
             config,
             per_account: HashMap::new(),
-            global_queue: BinaryHeap::new(),
+            global_queue: CappedSet::default(),
+            tails: CappedSet::default(),
             total_txs: 0,
+            local_txs: 0,
+            nonce_provider: Box::new(nonce_provider),
+            state_nonces: HashMap::new(),
+            base_fee: 0,
+            pending_floors: BTreeSet::new(),
+            parked_floors: BTreeSet::new(),
+            head_thresholds: BTreeSet::new(),
+            tail_thresholds: BTreeSet::new(),
+            clock: Box::new(SystemClock),
+            oldest_insertions: BTreeSet::new(),
+            validator: Box::new(NoopValidator),
+            listeners: Vec::new(),
         }
     }
 
-    /// 트랜잭션을 pool에 삽입하고 필요 시 lazy eviction을 트리거합니다.
-    pub fn insert(&mut self, tx: PendingTransaction) -> Result<(), TxInsertError> {
-        // sender는 HashMap key이자 오류 메시지에 필요하므로 선 복사합니다.
-        let sender = tx.sender.clone();
+    /// 삽입 시각을 찍을 시계를 바꿉니다. 기본은 `SystemClock`입니다.
+    pub fn with_clock(mut self, clock: impl Clock + Send + Sync + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
 
-        // 기존 큐 존재 여부를 조사해 중복 nonce와 계정별 슬롯 제한을 확인합니다.
-        if let Some(queue) = self.per_account.get(&sender) {
-            // 같은 sender에서 같은 nonce가 발견되면 DuplicateNonce를 즉시 반환합니다.
-            if queue.iter().any(|existing| existing.nonce == tx.nonce) {
-                return Err(TxInsertError::DuplicateNonce {
-                    sender: sender.clone(),
-                    nonce: tx.nonce,
-                });
-            }
-            // 계정별 슬롯 상한을 초과하면 AccountLimitReached를 반환합니다.
-            if queue.len() >= self.config.max_account_slots {
-                return Err(TxInsertError::AccountLimitReached {
-                    sender: sender.clone(),
-                });
-            }
-        } else if self.config.max_account_slots == 0 {
-            // 신규 sender인데 계정 슬롯이 0이면 즉시 거부합니다.
-            return Err(TxInsertError::AccountLimitReached { sender });
-        }
+    /// insert에서 쓸 검사기를 바꿉니다. 기본은 모두 통과시키는 `NoopValidator`입니다.
+    /// StateValidator와 nonce 조회기가 같은 계정 상태를 보도록 `Arc`로 감싼 상태를 양쪽에 넘길 수 있습니다.
+    pub fn with_validator(
+        mut self,
+        validator: impl TransactionValidator + Send + Sync + 'static,
+    ) -> Self {
+        self.validator = Box::new(validator);
+        self
+    }
 
-        // capacity에 도달했다면 축출을 먼저 시도하고, 새 트랜잭션은 거부(PoolFull)합니다.
-        if self.total_txs >= self.config.capacity {
-            self.evict_lowest_priority();
-            return Err(TxInsertError::PoolFull);
-        }
+    /// 이후 발생하는 PoolEvent를 받을 수신기를 돌려줍니다. 여러 번 호출하면 구독자마다 같은 이벤트를 받습니다.
+    pub fn subscribe(&mut self) -> mpsc::Receiver<PoolEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.add_listener(sender);
+        receiver
+    }
 
-        // front 비교를 위해 삽입 전의 해시를 저장합니다.
-        let previous_front_hash = self
-            .per_account
-            .get(&sender)
-            .and_then(|queue| queue.front().map(|front| front.hash.clone()));
+    /// TxPoolHandle이 모든 shard의 이벤트를 한 수신기로 모을 때 씁니다.
+    pub(crate) fn add_listener(&mut self, listener: mpsc::Sender<PoolEvent>) {
+        self.listeners.push(listener);
+    }
 
-        // VecDeque를 준비하고 nonce 기준으로 올바른 위치에 삽입합니다.
-        let queue = self
-            .per_account
-            .entry(sender.clone())
-            .or_insert_with(VecDeque::new);
-        let insert_pos = queue
-            .iter()
-            .position(|existing| existing.nonce > tx.nonce)
-            .unwrap_or(queue.len());
-        queue.insert(insert_pos, tx);
+    /// 풀이 보유한 전체 트랜잭션 수(pending + parked + queued)입니다.
+    pub fn len(&self) -> usize {
+        self.total_txs
+    }
 
-        // 총 트랜잭션 수를 갱신합니다. (per_account 합과 동일해야 함)
-        self.total_txs += 1;
+    pub fn is_empty(&self) -> bool {
+        self.total_txs == 0
+    }
 
-        // front가 바뀌었으면 BinaryHeap에 새 head를 푸시합니다(기존 head는 lazy eviction으로 제거).
-        let new_front_hash = queue.front().map(|front| front.hash.clone());
-        if new_front_hash != previous_front_hash {
-            if let Some(front) = queue.front().cloned() {
-                self.global_queue.push(QueuedTx::new(front));
-            }
-        }
+    /// 로컬 sender 트랜잭션 수로, local_capacity와 비교하는 값입니다.
+    pub fn local_len(&self) -> usize {
+        self.local_txs
+    }
 
-        Ok(())
+    /// 바로 실행 가능한 pending 트랜잭션 수입니다.
+    pub fn pending_len(&self) -> usize {
+        self.per_account.values().map(|queue| queue.pending).sum()
     }
 
-    /// lazy eviction으로 최소 우선순위 트랜잭션을 하나 제거합니다.
-    fn evict_lowest_priority(&mut self) {
-        // lazy eviction 순서: 힙 pop → stale 판별 → per_account 제거 → total_txs 감소 → front 재등록
-        let mut buffer: Vec<QueuedTx> = Vec::new();
-        while let Some(queued) = self.global_queue.pop() {
-            // stale 체커 체크리스트:
-            // 1. per_account.get(&queued.tx.sender)가 None이면 stale.
-            // 2. 큐가 존재해도 front()가 동일 hash·nonce가 아니면 stale.
-            // 3. stale이면 continue로 건너뛰고 다음 힙 항목 확인.
-            let sender = queued.tx.sender.clone();
-            let hash = queued.tx.hash.clone();
-            let nonce = queued.tx.nonce;
+    /// base fee 미달로 멈춰 있는 parked 트랜잭션 수입니다.
+    pub fn parked_len(&self) -> usize {
+        self.per_account.values().map(|queue| queue.parked).sum()
+    }
 
-            let is_stale = match self.per_account.get(sender.as_str()) {
-                None => true,
-                Some(queue) => match queue.front() {
-                    Some(front) if front.hash == hash && front.nonce == nonce => false,
-                    _ => true,
-                },
-            };
-            if is_stale {
-                continue;
-            }
-            buffer.push(queued);
+    /// nonce 빈틈 때문에 대기 중인 queued 트랜잭션 수입니다.
+    pub fn queued_len(&self) -> usize {
+        self.per_account.values().map(SenderQueue::queued).sum()
+    }
+
+    /// 현재 base fee입니다.
+    pub fn base_fee(&self) -> u64 {
+        self.base_fee
+    }
+
+    /// 풀의 모든 트랜잭션을 sub-pool과 함께 돌려줍니다. sender 안에서는 nonce 오름차순이고 sender 순서는 정해져 있지 않습니다.
+    pub fn transactions(&self) -> impl Iterator<Item = (SubPool, &PendingTransaction)> {
+        self.per_account
+            .values()
+            .flat_map(SenderQueue::txs_with_sub_pool)
+    }
+
+    /// 풀에 있는 `(sender, nonce)` 트랜잭션이 속한 sub-pool입니다.
+    pub fn sub_pool(&self, sender: &Pubkey, nonce: u64) -> Option<SubPool> {
+        self.per_account.get(sender)?.sub_pool(nonce)
+    }
+
+    /// 트랜잭션을 pool에 삽입하고, capacity에 도달했으면 가장 나쁜 꼬리 하나를 축출합니다.
+    pub fn insert(&mut self, tx: PendingTransaction) -> Result<InsertOutcome, TxInsertError> {
+        // sender는 HashMap key이자 오류 메시지에 필요하므로 선 복사합니다. (Pubkey는 Copy라 할당이 없습니다)
+        let sender = tx.sender;
+        let nonce = tx.nonce;
+
+        // 풀 규칙보다 먼저 트랜잭션 자체를 검사합니다. 교체 대상의 비용은 곧 빠지므로 합계에서 뺍니다.
+        let pooled_cost = self.per_account.get(&sender).map_or(0, |queue| {
+            queue
+                .txs
+                .values()
+                .filter(|pooled| pooled.nonce != nonce)
+                .fold(0u128, |sum, pooled| sum.saturating_add(pooled.cost()))
+        });
+        self.validator.validate(&tx, pooled_cost)?;
+
+        // state nonce보다 낮은 nonce는 이미 실행된 자리이므로 어떤 sub-pool에도 넣지 않습니다.
+        let state_nonce = self.state_nonce(sender);
+        if nonce < state_nonce {
+            return Err(TxInsertError::NonceTooLow {
+                sender,
+                nonce,
+                state_nonce,
+            });
         }
 
-        // 유효한 front가 없으면 축출할 것이 없으니 종료합니다.
-        if buffer.is_empty() {
-            // 유효한 항목이 하나도 없었다면 조용히 종료합니다.
-            return;
+        // 같은 nonce가 이미 있으면 교체 요청입니다. 보유 수가 변하지 않으므로 슬롯·capacity 검사보다 먼저 처리합니다.
+        if let Some(existing) = self
+            .per_account
+            .get(&sender)
+            .and_then(|queue| queue.txs.get(&nonce))
+        {
+            check_replacement(existing, &tx, self.config.price_bump)?;
+            let hash = tx.hash.clone();
+            let before = self.snapshot(sender);
+            let now = self.clock.now();
+            let replaced = self
+                .per_account
+                .get_mut(&sender)
+                .and_then(|queue| queue.insert(tx, now));
+            // 수수료가 바뀌었으니 경계를 다시 계산합니다. 교체된 head·꼬리는 정렬 키가 달라 제자리를 다시 찾습니다.
+            // 새 hash는 이전 pending 목록에 없으므로 승격 목록에서 뺍니다. (새 삽입 경로와 같은 규칙)
+            let mut promoted = self.resettle(sender, before);
+            promoted.retain(|promoted_hash| *promoted_hash != hash);
+            let sub_pool = self.sub_pool(&sender, nonce).unwrap_or(SubPool::Queued);
+            if let Some(old) = &replaced {
+                self.emit(PoolEvent::Replaced {
+                    hash: old.hash.clone(),
+                    replaced_by: hash.clone(),
+                });
+            }
+            self.emit(PoolEvent::Added { hash, sub_pool });
+            return Ok(InsertOutcome {
+                sub_pool,
+                promoted,
+                replaced,
+                evicted: Vec::new(),
+            });
         }
 
-        // 우선순위 키가 가장 작은(= 최저 우선순위) 항목을 찾습니다.
-        let mut lowest_idx = 0usize;
-        for idx in 1..buffer.len() {
-            let current = &buffer[idx];
-            let lowest = &buffer[lowest_idx];
-            let is_lower_priority = current.key < lowest.key
-                || (current.key == lowest.key && current.tx.nonce > lowest.tx.nonce);
-            if is_lower_priority {
-                lowest_idx = idx;
+        // 로컬 sender는 슬롯 제한·축출 없이 예약된 local_capacity 안에서만 들어갑니다.
+        let local = self.config.local_senders.contains(&sender);
+        let mut evicted = Vec::new();
+        if local {
+            if self.local_txs >= self.config.local_capacity {
+                return Err(TxInsertError::PoolFull);
+            }
+        } else {
+            // 기존 큐 존재 여부를 조사해 계정별 슬롯 제한을 확인합니다.
+            if let Some(queue) = self.per_account.get(&sender) {
+                // 계정별 슬롯 상한을 초과하면 AccountLimitReached를 반환합니다. (모든 sub-pool 합산)
+                if queue.len() >= self.config.max_account_slots {
+                    return Err(TxInsertError::AccountLimitReached { sender });
+                }
+            } else if self.config.max_account_slots == 0 {
+                // 신규 sender인데 계정 슬롯이 0이면 즉시 거부합니다.
+                return Err(TxInsertError::AccountLimitReached { sender });
+            }
+
+            // 공개 capacity에 도달했다면 새 트랜잭션이 가장 나쁜 꼬리보다 나을 때만 그 꼬리를 밀어내고 들어갑니다.
+            // tails에는 로컬 sender가 없으므로 로컬 트랜잭션은 밀려나지 않습니다.
+            if self.total_txs - self.local_txs >= self.config.capacity {
+                let incoming_rank = self.eviction_rank(&tx, state_nonce);
+                let Some((victim_sender, victim_nonce)) = self.worst_tail(&tx, incoming_rank)
+                else {
+                    return Err(TxInsertError::PoolFull);
+                };
+                evicted.extend(
+                    self.remove_tx(victim_sender, victim_nonce)
+                        .map(|victim| victim.hash),
+                );
             }
         }
 
-        let lowest = buffer.swap_remove(lowest_idx);
+        // nonce 자리에 넣고 총 트랜잭션 수를 갱신합니다. (per_account 합과 동일해야 함)
+        let before = self.snapshot(sender);
+        let hash = tx.hash.clone();
+        let now = self.clock.now();
+        self.per_account
+            .entry(sender)
+            .or_insert_with(|| SenderQueue {
+                local,
+                ..SenderQueue::default()
+            })
+            .insert(tx, now);
+        self.total_txs += 1;
+
+        // 빈틈이 채워졌거나 base fee를 감당하면 뒤따르는 트랜잭션까지 pending으로 올라갑니다.
+        let mut promoted = self.resettle(sender, before);
+        promoted.retain(|promoted_hash| *promoted_hash != hash);
 
-        // 나머지 front는 다시 힙으로 되돌립니다.
-        for queued in buffer {
-            self.global_queue.push(queued);
+        let sub_pool = self.sub_pool(&sender, nonce).unwrap_or(SubPool::Queued);
+        for victim in &evicted {
+            self.emit(PoolEvent::Evicted {
+                hash: victim.clone(),
+            });
         }
+        self.emit(PoolEvent::Added { hash, sub_pool });
+        Ok(InsertOutcome {
+            sub_pool,
+            promoted,
+            replaced: None,
+            evicted,
+        })
+    }
 
-        let sender = lowest.tx.sender.clone();
-        let mut remove_sender = false;
-        let mut new_front: Option<PendingTransaction> = None;
+    /// base fee를 바꾸고 sub-pool 경계와 head 정렬을 다시 맞춥니다. 새로 pending에 오른 hash를 돌려줍니다.
+    /// 경계를 넘는 sender와 head·꼬리만 인덱스 범위 조회로 찾으므로, 나머지 집합 항목은 건드리지 않습니다.
+    pub fn set_base_fee(&mut self, base_fee: u64) -> Vec<String> {
+        // 스냅숏은 옛 base fee로 찍어야 head·꼬리의 집합 구분 변화가 드러납니다.
+        let snapshots: Vec<(Pubkey, SenderKeys)> = self
+            .base_fee_affected(base_fee)
+            .into_iter()
+            .map(|sender| (sender, self.snapshot(sender)))
+            .collect();
+        self.base_fee = base_fee;
+        snapshots
+            .into_iter()
+            .flat_map(|(sender, before)| self.resettle(sender, before))
+            .collect()
+    }
 
-        if let Some(queue) = self.per_account.get_mut(sender.as_str()) {
-            // front가 반드시 동일해야 하므로 pop_front로 제거합니다.
-            let removed = queue.pop_front();
-            if removed.is_some() && self.total_txs > 0 {
-                self.total_txs -= 1;
-            }
+    /// base fee를 `base_fee`로 바꿀 때 sub-pool 경계나 head·꼬리의 집합 구분이 바뀌는 sender입니다.
+    fn base_fee_affected(&self, base_fee: u64) -> BTreeSet<Pubkey> {
+        let previous = self.base_fee;
+        if base_fee == previous {
+            return BTreeSet::new();
+        }
+
+        let mut affected: BTreeSet<Pubkey> = if base_fee > previous {
+            // pending 중 max_fee가 새 base fee에 못 미치는 sender는 그 nonce부터 parked로 내려갑니다.
+            self.pending_floors
+                .range(..(base_fee, Pubkey::default()))
+                .map(|&(_, sender)| sender)
+                .collect()
+        } else {
+            // parked 첫 트랜잭션이 새 base fee를 감당하면 pending으로 올라옵니다.
+            self.parked_floors
+                .range((base_fee, Pubkey::default())..)
+                .map(|&(_, sender)| sender)
+                .collect()
+        };
+        // tip_threshold가 두 base fee 사이에 있는 head·꼬리는 effective tip을 결정하는 상한이 바뀝니다.
+        let (low, high) = (previous.min(base_fee), previous.max(base_fee));
+        let crossing = (low, Pubkey::default())..(high, Pubkey::default());
+        affected.extend(
+            self.head_thresholds
+                .range(crossing.clone())
+                .chain(self.tail_thresholds.range(crossing))
+                .map(|&(_, sender)| sender),
+        );
+        affected
+    }
+
+    /// 모든 구독자에게 이벤트를 보내고, 수신기를 버린 구독자는 목록에서 뺍니다.
+    fn emit(&mut self, event: PoolEvent) {
+        self.listeners
+            .retain(|listener| listener.send(event.clone()).is_ok());
+    }
+
+    /// 캐시된 state nonce를 돌려주고, 처음 보는 sender라면 조회기에서 읽어 캐시에 둡니다.
+    fn state_nonce(&mut self, sender: Pubkey) -> u64 {
+        *self
+            .state_nonces
+            .entry(sender)
+            .or_insert_with(|| self.nonce_provider.account_nonce(&sender))
+    }
+
+    /// 변경 전 sender의 인덱스 키를 찍어 둡니다. 풀에 없는 sender는 빈 스냅숏입니다.
+    fn snapshot(&self, sender: Pubkey) -> SenderKeys {
+        self.per_account
+            .get(&sender)
+            .map(|queue| queue.keys(self.base_fee))
+            .unwrap_or_default()
+    }
 
+    /// sender 큐를 바꾼 뒤 호출합니다. sub-pool 경계를 다시 계산하고, 인덱스와 global_queue·tails를 갱신한 뒤
+    /// 새로 pending에 들어온 hash를 돌려줍니다. 비어 버린 sender는 per_account에서 뺍니다.
+    fn resettle(&mut self, sender: Pubkey, before: SenderKeys) -> Vec<String> {
+        let state_nonce = self.state_nonce(sender);
+        let base_fee = self.base_fee;
+        let mut promoted = Vec::new();
+        let mut after = SenderKeys::default();
+
+        if let Some(queue) = self.per_account.get_mut(&sender) {
+            queue.settle(state_nonce, base_fee);
+            promoted = queue
+                .pending_txs()
+                .filter(|tx| !before.pending_hashes.contains(&tx.hash))
+                .map(|tx| tx.hash.clone())
+                .collect();
+            after = queue.keys(base_fee);
             if queue.is_empty() {
-                // 큐가 비면 sender를 HashMap에서 제거합니다.
-                remove_sender = true;
-            } else {
-                new_front = queue.front().cloned();
+                self.per_account.remove(&sender);
             }
         }
 
-        if remove_sender {
-            self.per_account.remove(sender.as_str());
-        } else if let Some(front) = new_front {
-            // front가 바뀌었으므로 새 head를 힙에 등록합니다.
-            self.global_queue.push(QueuedTx::new(front));
-        }
+        reindex(
+            &mut self.pending_floors,
+            sender,
+            before.pending_floor,
+            after.pending_floor,
+        );
+        reindex(
+            &mut self.parked_floors,
+            sender,
+            before.parked_floor,
+            after.parked_floor,
+        );
+        reindex(
+            &mut self.head_thresholds,
+            sender,
+            before.head.as_ref().map(|head| head.threshold),
+            after.head.as_ref().map(|head| head.threshold),
+        );
+        reindex(
+            &mut self.tail_thresholds,
+            sender,
+            before.tail.as_ref().map(|tail| tail.threshold),
+            after.tail.as_ref().map(|tail| tail.threshold),
+        );
+        reindex(
+            &mut self.oldest_insertions,
+            sender,
+            before.oldest,
+            after.oldest,
+        );
+        self.local_txs = self.local_txs - before.local_len + after.local_len;
+
+        // head·꼬리가 바뀌었거나 집합 구분이 바뀌었으면 옛 키를 빼고 새 키를 넣습니다.
+        self.global_queue.replace(before.head, after.head);
+        self.tails.replace(before.tail, after.tail);
+
+        promoted
+    }
+
+    /// 두 집합의 최고 head 중 현재 base fee에서 effective tip이 더 높은 쪽의 `(sender, nonce)`입니다.
+    fn best_head(&self) -> Option<(Pubkey, u64)> {
+        self.best_head_rank()
+            .map(|(_, _, Reverse(nonce), sender)| (sender, nonce))
+    }
 
-        // 최저 우선순위 하나를 제거했으므로 작업 종료입니다.
+    fn best_head_rank(&self) -> Option<BestRank> {
+        [TipCap::PriorityFee, TipCap::MaxFee]
+            .into_iter()
+            .filter_map(|cap| {
+                let head = self.global_queue.set(cap).last()?;
+                Some(head.best_rank(cap, self.base_fee))
+            })
+            .max()
     }
 
-    /// 우선순위가 높은 항목부터 최대 `limit`개를 배치로 꺼냅니다.
+    /// 축출 비교용 순위로, sub-pool이 먼저(queued < parked < pending)이고 같으면 effective tip, max_fee, priority 순입니다.
+    fn eviction_rank(&self, tx: &PendingTransaction, state_nonce: u64) -> EvictionRank {
+        let sub_pool = match self.per_account.get(&tx.sender) {
+            Some(queue) => queue.prospective_sub_pool(tx, state_nonce, self.base_fee),
+            None => SenderQueue::default().prospective_sub_pool(tx, state_nonce, self.base_fee),
+        };
+        let (effective_tip, priority) = tx.priority_key(self.base_fee);
+        (
+            sub_pool.retention_rank(),
+            effective_tip,
+            tx.max_fee_per_gas,
+            priority,
+        )
+    }
+
+    /// sender마다 꼬리(최고 nonce) 트랜잭션만 후보로 삼아, `incoming`보다 순위가 낮은 것 중 최악을 고릅니다.
+    /// 꼬리만 빼므로 어떤 sender의 nonce 사슬도 앞에서 끊기지 않습니다.
+    /// 같은 sender의 꼬리가 새 트랜잭션보다 낮은 nonce라면 새 트랜잭션의 선행 nonce이므로 후보에서 뺍니다.
+    /// 각 집합 안에서는 순위가 오름차순이라 앞에서 많아야 두 항목만 보면 되므로 O(log n)입니다.
+    fn worst_tail(
+        &self,
+        incoming: &PendingTransaction,
+        incoming_rank: EvictionRank,
+    ) -> Option<(Pubkey, u64)> {
+        let base_fee = self.base_fee;
+        [TipCap::PriorityFee, TipCap::MaxFee]
+            .into_iter()
+            .filter_map(|cap| {
+                self.tails
+                    .set(cap)
+                    .iter()
+                    .map(|tail| (tail.rank(cap, base_fee), tail))
+                    .take_while(|(rank, _)| *rank < incoming_rank)
+                    .find(|(_, tail)| {
+                        !(tail.sender == incoming.sender && tail.nonce < incoming.nonce)
+                    })
+            })
+            .min_by(|(a, a_tail), (b, b_tail)| {
+                (a, a_tail.sender, a_tail.nonce).cmp(&(b, b_tail.sender, b_tail.nonce))
+            })
+            .map(|(_, tail)| (tail.sender, tail.nonce))
+    }
+
+    /// `(sender, nonce)` 트랜잭션 하나를 빼고 sender를 다시 정리합니다.
+    /// 중간 nonce가 빠지면 뒤따르는 트랜잭션은 settle에서 queued로 내려갑니다.
+    fn remove_tx(&mut self, sender: Pubkey, nonce: u64) -> Option<PendingTransaction> {
+        let before = self.snapshot(sender);
+        let removed = self
+            .per_account
+            .get_mut(&sender)
+            .and_then(|queue| queue.remove(nonce))?;
+        self.total_txs -= 1;
+        self.resettle(sender, before);
+        Some(removed)
+    }
+
+    /// effective tip이 높은 pending 항목부터 최대 `limit`개를 배치로 꺼냅니다.
+    /// parked·queued는 global_queue에 오르지 않으므로 sender별로 빈틈 없는 nonce 순서만 반환됩니다.
     pub fn pop_batch(&mut self, limit: usize) -> PopResult {
         // 0을 요청하면 비어 있는 결과를 돌려줘 호출자가 실수로 0을 넣어도 혼란이 없습니다.
         if limit == 0 {
@@ -269,82 +1184,226 @@ impl TxPool {
         let mut drained = Vec::new();
 
         while drained.len() < limit {
-            // 힙에서 후보를 꺼내고 없으면 반복을 종료합니다.
-            let Some(queued) = self.global_queue.pop() else {
+            // 최고 head를 고르고 없으면 반복을 종료합니다.
+            let Some((sender, nonce)) = self.best_head() else {
                 break;
             };
 
-            // stale 체크 (README 체크리스트와 동일합니다).
-            // 1. per_account.get(&queued.tx.sender)가 None이면 stale.
-            // 2. front()의 hash/nonce가 일치하지 않으면 stale.
-            // 3. stale이면 continue.
-            let sender = queued.tx.sender.clone();
-            let hash = queued.tx.hash.clone();
-            let nonce = queued.tx.nonce;
-
-            let is_stale = match self.per_account.get(sender.as_str()) {
-                None => true,
-                Some(queue) => match queue.front() {
-                    Some(front) if front.hash == hash && front.nonce == nonce => false,
-                    _ => true,
-                },
-            };
-            if is_stale {
-                continue;
-            }
-
-            // front를 제거하고 drained에 담은 뒤, 다음 nonce를 힙에 재등록합니다.
-            let mut remove_sender = false;
-            let mut new_front: Option<PendingTransaction> = None;
-            let mut drained_tx: Option<PendingTransaction> = None;
-
-            if let Some(queue) = self.per_account.get_mut(sender.as_str()) {
-                let front = queue
-                    .pop_front()
-                    .expect("lazy eviction에서 stale이 아닌 front는 반드시 존재해야 합니다");
-                drained_tx = Some(front);
+            // 내보낸 nonce는 실행된 것으로 보고 state nonce를 다음 자리로 옮긴 뒤,
+            // head를 제거하고 drained에 담습니다. 다음 nonce는 resettle에서 global_queue에 올라갑니다.
+            self.state_nonces.insert(sender, nonce + 1);
+            let removed = self
+                .remove_tx(sender, nonce)
+                .expect("global_queue의 head는 반드시 풀에 존재해야 합니다");
+            drained.push(removed);
+        }
 
-                if self.total_txs > 0 {
-                    self.total_txs -= 1;
-                }
+        if drained.is_empty() {
+            PopResult::Empty
+        } else {
+            // drained가 priority_key 내림차순이 되도록 정렬합니다.
+            sort_drained(&mut drained, self.base_fee);
+            PopResult::Batch { drained }
+        }
+    }
+
+    /// canonical 블록을 반영해 mined·stale 트랜잭션을 빼고, 새 state nonce와 base fee 기준으로 sub-pool을 다시 나눕니다.
+    /// state nonce가 내려가는 경우(reorg)도 같은 규칙으로 처리합니다.
+    pub fn on_canonical_block(&mut self, block: CanonicalBlock) -> CanonicalUpdate {
+        let mut update = CanonicalUpdate::default();
 
-                if queue.is_empty() {
-                    remove_sender = true;
+        // sender별 새 state nonce: account_nonces가 우선이고, 없으면 mined 최고 nonce + 1로 추정합니다.
+        // sender 순서를 고정해 결과 목록과 이벤트 순서가 실행마다 같게 합니다.
+        let mut next_nonces: BTreeMap<Pubkey, u64> = BTreeMap::new();
+        // mined는 블록에서 온 입력이므로 nonce가 u64::MAX일 수 있습니다. 다음 자리가 없는 쌍은 추정에서 뺍니다.
+        for &(sender, nonce) in &block.mined {
+            let Some(after) = nonce.checked_add(1) else {
+                continue;
+            };
+            let cached = self.state_nonces.get(&sender).copied().unwrap_or(0);
+            let next = next_nonces.entry(sender).or_insert(cached);
+            *next = (*next).max(after);
+        }
+        next_nonces.extend(block.account_nonces);
+        let mined: HashSet<(Pubkey, u64)> = block.mined.into_iter().collect();
+
+        // state nonce와 base fee를 모두 반영한 뒤 sender마다 한 번만 다시 나눕니다.
+        // 따로 반영하면 옛 base fee로 잠깐 pending에 올랐다가 새 base fee로 다시 내려간 hash까지 승격으로 보고됩니다.
+        let mut affected: BTreeSet<Pubkey> = next_nonces.keys().copied().collect();
+        if let Some(base_fee) = block.base_fee {
+            affected.extend(self.base_fee_affected(base_fee));
+        }
+        // 스냅숏은 옛 base fee로 찍어야 head·꼬리의 집합 구분 변화가 드러납니다.
+        let snapshots: Vec<(Pubkey, SenderKeys)> = affected
+            .into_iter()
+            .map(|sender| (sender, self.snapshot(sender)))
+            .collect();
+
+        for (sender, state_nonce) in next_nonces {
+            self.state_nonces.insert(sender, state_nonce);
+            let Some(queue) = self.per_account.get_mut(&sender) else {
+                continue;
+            };
+
+            // state nonce 아래 자리는 이미 실행되었으므로 모두 뺍니다.
+            let executed = queue.split_below(state_nonce);
+            self.total_txs -= executed.len();
+            for (nonce, tx) in executed {
+                if mined.contains(&(sender, nonce)) {
+                    update.mined.push(tx);
                 } else {
-                    new_front = queue.front().cloned();
+                    update.discarded.push(tx);
                 }
             }
+        }
+        if let Some(base_fee) = block.base_fee {
+            self.base_fee = base_fee;
+        }
+
+        // 새 state nonce·base fee에서 구간을 다시 나누고, head·꼬리가 바뀐 sender만 인덱스를 갱신합니다.
+        for (sender, before) in snapshots {
+            update.promoted.extend(self.resettle(sender, before));
+        }
 
-            if remove_sender {
-                self.per_account.remove(sender.as_str());
-            } else if let Some(front) = new_front {
-                self.global_queue.push(QueuedTx::new(front));
+        for tx in &update.mined {
+            self.emit(PoolEvent::Mined {
+                hash: tx.hash.clone(),
+            });
+        }
+        for tx in &update.discarded {
+            self.emit(PoolEvent::Discarded {
+                hash: tx.hash.clone(),
+            });
+        }
+        update
+    }
+
+    /// reorg로 되돌려진 블록의 트랜잭션을 다시 풀에 넣습니다.
+    /// 새 canonical 블록을 먼저 반영해 state nonce를 되돌린 뒤 일반 insert 경로로 재삽입합니다.
+    pub fn on_reorg(
+        &mut self,
+        reverted: Vec<PendingTransaction>,
+        new_block: CanonicalBlock,
+    ) -> CanonicalUpdate {
+        let mut update = self.on_canonical_block(new_block);
+        for tx in reverted {
+            match self.insert(tx.clone()) {
+                Ok(outcome) => {
+                    update.reinjected.push(tx.hash);
+                    update.promoted.extend(outcome.promoted);
+                }
+                // NonceTooLow는 새 체인에 다시 포함되었다는 뜻이고, DuplicateNonce는 같은 hash가 아직 풀에 남아 있다는 뜻입니다.
+                // 둘 다 되돌려진 트랜잭션이 사라진 것이 아니므로 따로 보고하지 않습니다.
+                Err(TxInsertError::NonceTooLow { .. } | TxInsertError::DuplicateNonce { .. }) => {}
+                // ReplacementUnderpriced를 포함한 나머지는 풀에 되돌아가지 못했으므로 discarded로 보고합니다.
+                // 같은 nonce 자리를 다른 트랜잭션이 차지했다면 되돌려진 트랜잭션은 더 이상 실행될 수 없습니다.
+                Err(_) => {
+                    self.emit(PoolEvent::Discarded {
+                        hash: tx.hash.clone(),
+                    });
+                    update.discarded.push(tx);
+                }
             }
+        }
+        update
+    }
+
+    /// 삽입 후 `max_age`보다 오래된 트랜잭션을 빼고, 같은 sender의 더 높은 nonce도 함께 뺍니다.
+    /// 앞선 nonce가 사라지면 뒤따르는 트랜잭션은 영원히 실행될 수 없으므로 슬롯만 차지하기 때문입니다.
+    /// 가장 이른 삽입 시각 인덱스로 만료된 sender만 찾으므로 주기적으로 불러도 풀 전체를 훑지 않습니다.
+    pub fn prune_expired(&mut self, now: Instant) -> PruneOutcome {
+        let mut outcome = PruneOutcome::default();
+        let Some(cutoff) = now.checked_sub(self.config.max_age) else {
+            return outcome;
+        };
 
-            if let Some(tx) = drained_tx {
-                drained.push(tx);
+        let senders: Vec<Pubkey> = self
+            .oldest_insertions
+            .range(..(cutoff, Pubkey::default()))
+            .map(|&(_, sender)| sender)
+            .collect();
+        for sender in senders {
+            let before = self.snapshot(sender);
+            let Some(queue) = self.per_account.get_mut(&sender) else {
+                continue;
+            };
+            let Some(first_expired) = queue.first_expired(cutoff) else {
+                continue;
+            };
+
+            let dropped = queue.split_from(first_expired);
+            self.total_txs -= dropped.len();
+            for (tx, inserted_at) in dropped {
+                if inserted_at < cutoff {
+                    outcome.expired.push(tx);
+                } else {
+                    outcome.dependents.push(tx);
+                }
             }
+            // 꼬리 쪽만 빠지므로 승격은 없고, head·꼬리와 인덱스만 다시 맞춥니다.
+            self.resettle(sender, before);
         }
 
-        if drained.is_empty() {
-            PopResult::Empty
-        } else {
-            // drained가 priority_key 내림차순이 되도록 정렬합니다.
-            drained.sort_by(|a, b| {
-                b.priority_key()
-                    .cmp(&a.priority_key())
-                    .then(a.nonce.cmp(&b.nonce))
+        for tx in &outcome.expired {
+            self.emit(PoolEvent::Expired {
+                hash: tx.hash.clone(),
+            });
+        }
+        for tx in &outcome.dependents {
+            self.emit(PoolEvent::Discarded {
+                hash: tx.hash.clone(),
             });
-            PopResult::Batch { drained }
         }
+        outcome
+    }
+}
+
+/// pop_batch 결과를 priority_key 내림차순, 같으면 nonce 오름차순으로 정렬합니다.
+fn sort_drained(drained: &mut [PendingTransaction], base_fee: u64) {
+    drained.sort_by(|a, b| {
+        b.priority_key(base_fee)
+            .cmp(&a.priority_key(base_fee))
+            .then(a.nonce.cmp(&b.nonce))
+    });
+}
+
+/// 같은 nonce 자리의 `existing`을 `tx`로 바꿀 수 있는지 검사합니다.
+/// max_fee와 팁 상한이 모두 `price_bump`% 이상 올라야 교체하며, 같은 hash 재제출은 DuplicateNonce입니다.
+fn check_replacement(
+    existing: &PendingTransaction,
+    tx: &PendingTransaction,
+    price_bump: u64,
+) -> Result<(), TxInsertError> {
+    if existing.hash == tx.hash {
+        return Err(TxInsertError::DuplicateNonce {
+            sender: tx.sender,
+            nonce: tx.nonce,
+        });
     }
+    let min_max_fee_per_gas = bumped_price(existing.max_fee_per_gas, price_bump);
+    let min_max_priority_fee_per_gas = bumped_price(existing.max_priority_fee_per_gas, price_bump);
+    if tx.max_fee_per_gas < min_max_fee_per_gas
+        || tx.max_priority_fee_per_gas < min_max_priority_fee_per_gas
+    {
+        return Err(TxInsertError::ReplacementUnderpriced {
+            sender: tx.sender,
+            nonce: tx.nonce,
+            min_max_fee_per_gas,
+            min_max_priority_fee_per_gas,
+        });
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::{Arc, Mutex};
+
+    // 아래 도우미는 persist·handle·best·validate·invariants 모듈의 테스트도 함께 씁니다.
 
-    fn make_tx(
+    // 레거시 트랜잭션: gas_price가 max_fee이자 팁 상한입니다.
+    pub(crate) fn make_tx(
         sender: &str,
         nonce: u64,
         priority: u128,
@@ -353,10 +1412,76 @@ mod tests {
     ) -> PendingTransaction {
         PendingTransaction {
             hash: format!("{sender}-{nonce}-{tag}"),
-            sender: sender.to_string(),
+            sender: Pubkey::from_name(sender),
             nonce,
-            gas_price,
+            max_fee_per_gas: gas_price,
+            max_priority_fee_per_gas: gas_price,
             priority,
+            gas_limit: 21_000,
+            value: 0,
+        }
+    }
+
+    pub(crate) fn make_1559_tx(
+        sender: &str,
+        nonce: u64,
+        max_fee: u64,
+        tip: u64,
+        tag: &str,
+    ) -> PendingTransaction {
+        PendingTransaction {
+            max_fee_per_gas: max_fee,
+            max_priority_fee_per_gas: tip,
+            ..make_tx(sender, nonce, 0, 0, tag)
+        }
+    }
+
+    // sender의 특정 sub-pool에 있는 nonce 목록입니다.
+    pub(crate) fn nonces_in(pool: &TxPool, sender: &str, sub_pool: SubPool) -> Vec<u64> {
+        let Some(queue) = pool.per_account.get(&Pubkey::from_name(sender)) else {
+            return Vec::new();
+        };
+        queue
+            .txs
+            .keys()
+            .copied()
+            .filter(|&nonce| queue.sub_pool(nonce) == Some(sub_pool))
+            .collect()
+    }
+
+    // 테스트가 직접 움직이는 시계입니다. 돌려준 Mutex를 advance로 앞당기면 풀의 삽입 시각도 따라갑니다.
+    pub(crate) fn manual_clock() -> (Arc<Mutex<Instant>>, impl Clock + Send + Sync + 'static) {
+        let now = Arc::new(Mutex::new(Instant::now()));
+        let shared = Arc::clone(&now);
+        (now, move || *shared.lock().unwrap())
+    }
+
+    pub(crate) fn advance(now: &Mutex<Instant>, secs: u64) -> Instant {
+        let mut now = now.lock().unwrap();
+        *now += Duration::from_secs(secs);
+        *now
+    }
+
+    pub(crate) fn hashes_of(txs: &[PendingTransaction]) -> Vec<&str> {
+        txs.iter().map(|tx| tx.hash.as_str()).collect()
+    }
+
+    // 테스트마다 겹치지 않는 임시 스냅숏 경로입니다.
+    pub(crate) fn snapshot_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("tx_pool-{}-{name}.snapshot", std::process::id()))
+    }
+
+    pub(crate) fn added(hash: &str, sub_pool: SubPool) -> PoolEvent {
+        PoolEvent::Added {
+            hash: hash.to_string(),
+            sub_pool,
+        }
+    }
+
+    pub(crate) fn drain_hashes(pool: &mut TxPool, limit: usize) -> Vec<String> {
+        match pool.pop_batch(limit) {
+            PopResult::Batch { drained } => drained.into_iter().map(|tx| tx.hash).collect(),
+            PopResult::Empty => Vec::new(),
         }
     }
 
@@ -366,6 +1491,7 @@ mod tests {
         let config = TxPoolConfig {
             capacity: 16,
             max_account_slots: 8,
+            ..TxPoolConfig::default()
         };
         // When: TxPool::new 호출
         let pool = TxPool::new(config);
@@ -381,6 +1507,7 @@ mod tests {
         let config = TxPoolConfig {
             capacity: 10,
             max_account_slots: 10,
+            ..TxPoolConfig::default()
         };
         let mut pool = TxPool::new(config);
         // When: 순서대로 insert
@@ -391,9 +1518,7 @@ mod tests {
         pool.insert(make_tx("alice", 1, 9, 95, "c"))
             .expect("third insert");
         // Then: per_account 큐 nonce [0,1,2]
-        let queue = pool.per_account.get("alice").expect("alice queue");
-        let nonces: Vec<u64> = queue.iter().map(|tx| tx.nonce).collect();
-        assert_eq!(nonces, vec![0, 1, 2]);
+        assert_eq!(nonces_in(&pool, "alice", SubPool::Pending), vec![0, 1, 2]);
     }
 
     #[test]
@@ -402,6 +1527,7 @@ mod tests {
         let config = TxPoolConfig {
             capacity: 10,
             max_account_slots: 10,
+            ..TxPoolConfig::default()
         };
         let mut pool = TxPool::new(config);
         // When: 두 번 insert
@@ -409,11 +1535,15 @@ mod tests {
             .expect("first insert");
         pool.insert(make_tx("alice", 1, 4, 110, "b"))
             .expect("second insert");
-        // Then: global_queue.len == 1, head hash == nonce 0
+        // Then: global_queue.len == 1, head == nonce 0, 레거시 tx는 팁 상한 집합에 gas_price로 정렬
         assert_eq!(pool.global_queue.len(), 1);
-        let head = pool.global_queue.peek().expect("head exists");
-        assert_eq!(head.tx.nonce, 0);
-        assert_eq!(head.key, (5, 100));
+        let head = pool
+            .global_queue
+            .by_priority_fee
+            .last()
+            .expect("head exists");
+        assert_eq!(head.nonce, Reverse(0));
+        assert_eq!((head.fee, head.priority), (100, 5));
     }
 
     #[test]
@@ -422,12 +1552,13 @@ mod tests {
         let config = TxPoolConfig {
             capacity: 10,
             max_account_slots: 10,
+            ..TxPoolConfig::default()
         };
         let mut pool = TxPool::new(config);
         pool.insert(make_tx("alice", 0, 5, 100, "a"))
             .expect("initial insert");
-        // When: nonce 0 재삽입
-        let duplicated = make_tx("alice", 0, 6, 120, "dup");
+        // When: 같은 hash의 nonce 0 재삽입
+        let duplicated = make_tx("alice", 0, 5, 100, "a");
         let result = pool.insert(duplicated);
         // Then: Err DuplicateNonce, 큐 길이 유지
         assert!(matches!(
@@ -435,10 +1566,9 @@ mod tests {
             Err(TxInsertError::DuplicateNonce {
                 sender,
                 nonce: 0
-            }) if sender == "alice"
+            }) if sender == Pubkey::from_name("alice")
         ));
-        let queue = pool.per_account.get("alice").expect("alice queue");
-        assert_eq!(queue.len(), 1);
+        assert_eq!(pool.per_account[&Pubkey::from_name("alice")].len(), 1);
     }
 
     #[test]
@@ -447,6 +1577,7 @@ mod tests {
         let config = TxPoolConfig {
             capacity: 10,
             max_account_slots: 2,
+            ..TxPoolConfig::default()
         };
         let mut pool = TxPool::new(config);
         pool.insert(make_tx("alice", 0, 5, 100, "a"))
@@ -458,10 +1589,9 @@ mod tests {
         // Then: Err AccountLimitReached, 큐 길이 2
         assert!(matches!(
             result,
-            Err(TxInsertError::AccountLimitReached { sender }) if sender == "alice"
+            Err(TxInsertError::AccountLimitReached { sender }) if sender == Pubkey::from_name("alice")
         ));
-        let queue = pool.per_account.get("alice").expect("alice queue");
-        assert_eq!(queue.len(), 2);
+        assert_eq!(pool.per_account[&Pubkey::from_name("alice")].len(), 2);
     }
 
     #[test]
@@ -470,6 +1600,7 @@ mod tests {
         let config = TxPoolConfig {
             capacity: 3,
             max_account_slots: 3,
+            ..TxPoolConfig::default()
         };
         let mut pool = TxPool::new(config);
         let high = make_tx("alice", 0, 10, 100, "high");
@@ -478,18 +1609,24 @@ mod tests {
         pool.insert(high.clone()).unwrap();
         pool.insert(mid.clone()).unwrap();
         pool.insert(low.clone()).unwrap();
-        // When: 4번째 insert
+        // When: 가장 나쁜 tx보다도 낮은 4번째 insert
         let extra_low = make_tx("dave", 0, 1, 90, "extra");
         let result = pool.insert(extra_low.clone());
-        // Then: Err PoolFull, 낮은 우선순위 hash가 제거됨
+        // Then: Err PoolFull, 아무것도 축출되지 않음
         assert!(matches!(result, Err(TxInsertError::PoolFull)));
+        assert_eq!(pool.len(), 3);
+        // When: 가장 나쁜 tx(carol)보다 나은 insert
+        let better = make_tx("erin", 0, 1, 150, "better");
+        let outcome = pool.insert(better.clone()).expect("better tx is admitted");
+        // Then: carol이 축출되고 새 tx가 들어옴
+        assert_eq!(outcome.evicted, vec![low.hash.clone()]);
         let remaining_hashes: Vec<String> = pool
             .per_account
             .values()
-            .flat_map(|queue| queue.iter().map(|tx| tx.hash.clone()))
+            .flat_map(|queue| queue.txs.values().map(|tx| tx.hash.clone()))
             .collect();
         assert!(!remaining_hashes.contains(&low.hash));
-        assert!(!remaining_hashes.contains(&extra_low.hash));
+        assert!(remaining_hashes.contains(&better.hash));
         assert!(pool.total_txs <= pool.config.capacity);
     }
 
@@ -499,6 +1636,7 @@ mod tests {
         let config = TxPoolConfig {
             capacity: 10,
             max_account_slots: 3,
+            ..TxPoolConfig::default()
         };
         let mut pool = TxPool::new(config);
         pool.insert(make_tx("alice", 0, 8, 100, "a"))
@@ -509,12 +1647,12 @@ mod tests {
             .expect("carol insert");
         // When: pop_batch(limit=3)
         let result = pool.pop_batch(3);
-        // Then: drained priority_key 내림차순
+        // Then: drained priority_key(effective tip, priority) 내림차순
         let PopResult::Batch { drained } = result else {
             panic!("expected drained batch");
         };
-        let keys: Vec<(u128, u64)> = drained.iter().map(|tx| tx.priority_key()).collect();
-        assert_eq!(keys, vec![(10, 90), (8, 120), (8, 100)]);
+        let keys: Vec<(u64, u128)> = drained.iter().map(|tx| tx.priority_key(0)).collect();
+        assert_eq!(keys, vec![(120, 8), (100, 8), (90, 10)]);
     }
 
     #[test]
@@ -523,6 +1661,7 @@ mod tests {
         let config = TxPoolConfig {
             capacity: 10,
             max_account_slots: 3,
+            ..TxPoolConfig::default()
         };
         let mut pool = TxPool::new(config);
         pool.insert(make_tx("alice", 0, 5, 100, "a0"))
@@ -538,31 +1677,1272 @@ mod tests {
         assert_eq!(drained.len(), 2);
         assert_eq!(drained[0].hash, "bob-0-b0");
         assert_eq!(drained[1].hash, "alice-0-a0");
-        let alice_queue = pool.per_account.get("alice").expect("alice queue");
-        assert_eq!(alice_queue.len(), 1);
-        assert_eq!(alice_queue.front().unwrap().hash, "alice-1-a1");
-        let head = pool.global_queue.peek().expect("next head");
-        assert_eq!(head.tx.hash, "alice-1-a1");
+        assert_eq!(nonces_in(&pool, "alice", SubPool::Pending), vec![1]);
+        assert_eq!(drain_hashes(&mut pool, 1), vec!["alice-1-a1"]);
     }
 
     #[test]
-    fn lazy_eviction_skips_stale_entries_on_pop() {
-        // Given: evict 후 stale 남긴 상태
+    fn evicted_head_leaves_no_entry_to_pop() {
+        // Given: capacity 1에 alice head 하나
         let config = TxPoolConfig {
             capacity: 1,
             max_account_slots: 1,
+            ..TxPoolConfig::default()
         };
         let mut pool = TxPool::new(config);
         pool.insert(make_tx("alice", 0, 5, 100, "a"))
             .expect("initial insert");
-        // 새 트랜잭션으로 인해 eviction을 시도하게 만들어 stale 항목이 힙에 남습니다.
-        let result = pool.insert(make_tx("bob", 0, 4, 90, "b"));
-        assert!(matches!(result, Err(TxInsertError::PoolFull)));
+        // 더 나은 트랜잭션이 alice를 축출하면 alice의 head·꼬리 항목도 즉시 빠집니다.
+        let outcome = pool.insert(make_tx("bob", 0, 6, 110, "b")).unwrap();
+        assert_eq!(outcome.evicted, vec!["alice-0-a"]);
+        assert_eq!(pool.global_queue.len(), 1);
+        assert_eq!(pool.tails.len(), 1);
         // When: pop_batch
-        let pop_result = pool.pop_batch(1);
-        // Then: drained에 stale 없음, 구조 일관성 유지
-        assert!(matches!(pop_result, PopResult::Empty));
+        let pop_result = pool.pop_batch(2);
+        // Then: drained에 축출된 alice 없음, 구조 일관성 유지
+        let PopResult::Batch { drained } = pop_result else {
+            panic!("expected batch");
+        };
+        let hashes: Vec<&str> = drained.iter().map(|tx| tx.hash.as_str()).collect();
+        assert_eq!(hashes, vec!["bob-0-b"]);
         assert_eq!(pool.total_txs, 0);
         assert!(pool.per_account.is_empty());
+        assert!(pool.global_queue.is_empty());
+        assert!(pool.tails.is_empty());
+    }
+
+    #[test]
+    fn insert_with_nonce_gap_goes_to_queued() {
+        // Given: 온체인 nonce 0인 alice
+        let config = TxPoolConfig {
+            capacity: 10,
+            max_account_slots: 10,
+            ..TxPoolConfig::default()
+        };
+        let mut pool = TxPool::new(config);
+        // When: nonce 0, 2 insert
+        let first = pool.insert(make_tx("alice", 0, 5, 100, "a")).unwrap();
+        let gapped = pool.insert(make_tx("alice", 2, 5, 100, "c")).unwrap();
+        // Then: nonce 0은 Pending, nonce 2는 Queued이고 global_queue에는 nonce 0만 존재
+        assert_eq!(first.sub_pool, SubPool::Pending);
+        assert_eq!(gapped.sub_pool, SubPool::Queued);
+        assert_eq!(
+            (pool.pending_len(), pool.queued_len(), pool.len()),
+            (1, 1, 2)
+        );
+        assert_eq!(pool.global_queue.len(), 1);
+    }
+
+    #[test]
+    fn filling_nonce_gap_promotes_queued() {
+        // Given: alice nonce 0(pending), 2·3(queued)
+        let config = TxPoolConfig {
+            capacity: 10,
+            max_account_slots: 10,
+            ..TxPoolConfig::default()
+        };
+        let mut pool = TxPool::new(config);
+        pool.insert(make_tx("alice", 0, 5, 100, "a")).unwrap();
+        pool.insert(make_tx("alice", 3, 5, 100, "d")).unwrap();
+        pool.insert(make_tx("alice", 2, 5, 100, "c")).unwrap();
+        // When: 빈틈인 nonce 1 insert
+        let outcome = pool.insert(make_tx("alice", 1, 5, 100, "b")).unwrap();
+        // Then: nonce 2, 3이 순서대로 승격되고 queued는 비어 있음
+        assert_eq!(outcome.sub_pool, SubPool::Pending);
+        assert_eq!(outcome.promoted, vec!["alice-2-c", "alice-3-d"]);
+        assert_eq!(
+            nonces_in(&pool, "alice", SubPool::Pending),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(pool.queued_len(), 0);
+    }
+
+    #[test]
+    fn insert_uses_provider_nonce_and_rejects_stale_nonce() {
+        // Given: 온체인 nonce alice=5
+        let config = TxPoolConfig {
+            capacity: 10,
+            max_account_slots: 10,
+            ..TxPoolConfig::default()
+        };
+        let nonces = HashMap::from([(Pubkey::from_name("alice"), 5)]);
+        let mut pool = TxPool::with_nonce_provider(config, nonces);
+        // When: nonce 3, 6, 5 insert
+        let stale = pool.insert(make_tx("alice", 3, 5, 100, "old"));
+        let future = pool.insert(make_tx("alice", 6, 5, 100, "f")).unwrap();
+        let next = pool.insert(make_tx("alice", 5, 5, 100, "n")).unwrap();
+        // Then: nonce 3은 NonceTooLow, nonce 6은 queued였다가 nonce 5가 들어오며 승격
+        assert!(matches!(
+            stale,
+            Err(TxInsertError::NonceTooLow {
+                nonce: 3,
+                state_nonce: 5,
+                ..
+            })
+        ));
+        assert_eq!(future.sub_pool, SubPool::Queued);
+        assert_eq!(next.promoted, vec!["alice-6-f"]);
+        assert_eq!(pool.pending_len(), 2);
+    }
+
+    #[test]
+    fn pop_batch_yields_only_gap_free_sequences() {
+        // Given: alice 0,1,3 / 온체인 nonce 2인 bob의 4
+        let config = TxPoolConfig {
+            capacity: 10,
+            max_account_slots: 10,
+            ..TxPoolConfig::default()
+        };
+        let nonces = HashMap::from([(Pubkey::from_name("bob"), 2)]);
+        let mut pool = TxPool::with_nonce_provider(config, nonces);
+        pool.insert(make_tx("alice", 0, 5, 100, "a0")).unwrap();
+        pool.insert(make_tx("alice", 1, 5, 100, "a1")).unwrap();
+        pool.insert(make_tx("alice", 3, 9, 100, "a3")).unwrap();
+        pool.insert(make_tx("bob", 4, 9, 100, "b4")).unwrap();
+        // When: pop_batch(limit=10)
+        let PopResult::Batch { drained } = pool.pop_batch(10) else {
+            panic!("expected batch");
+        };
+        // Then: alice 0,1만 나오고 alice 3, bob 4는 queued에 남음
+        let hashes: Vec<&str> = drained.iter().map(|tx| tx.hash.as_str()).collect();
+        assert_eq!(hashes, vec!["alice-0-a0", "alice-1-a1"]);
+        assert_eq!((pool.pending_len(), pool.queued_len()), (0, 2));
+        // Then: 내보낸 nonce는 state nonce로 반영되어 재삽입은 거부, nonce 2는 빈틈을 채워 승격
+        assert!(matches!(
+            pool.insert(make_tx("alice", 1, 5, 100, "again")),
+            Err(TxInsertError::NonceTooLow { state_nonce: 2, .. })
+        ));
+        let outcome = pool.insert(make_tx("alice", 2, 5, 100, "a2")).unwrap();
+        assert_eq!(outcome.promoted, vec!["alice-3-a3"]);
+    }
+
+    #[test]
+    fn replace_by_fee_requires_price_bump() {
+        // Given: price_bump=10, alice nonce 0 (max_fee 100, 팁 상한 10)
+        let config = TxPoolConfig {
+            capacity: 10,
+            max_account_slots: 1,
+            ..TxPoolConfig::default()
+        };
+        let mut pool = TxPool::new(config);
+        pool.insert(make_1559_tx("alice", 0, 100, 10, "slow"))
+            .unwrap();
+        // When: 팁은 두 배로 올렸지만 max_fee는 5%만 올린 교체 시도
+        let underpriced = pool.insert(make_1559_tx("alice", 0, 105, 20, "meh"));
+        // Then: Err ReplacementUnderpriced, 최소 요구치는 (110, 11)
+        assert!(matches!(
+            underpriced,
+            Err(TxInsertError::ReplacementUnderpriced {
+                nonce: 0,
+                min_max_fee_per_gas: 110,
+                min_max_priority_fee_per_gas: 11,
+                ..
+            })
+        ));
+        // When: 두 값 모두 10% 이상 올려 교체 (슬롯이 가득 차 있어도 교체는 허용)
+        let outcome = pool
+            .insert(make_1559_tx("alice", 0, 110, 11, "fast"))
+            .unwrap();
+        // Then: 기존 tx를 돌려주고 pop_batch는 교체된 tx만 내보냄 (기존 head 항목은 교체 시 빠짐)
+        assert_eq!(outcome.sub_pool, SubPool::Pending);
+        assert_eq!(outcome.replaced.unwrap().hash, "alice-0-slow");
+        assert_eq!(pool.len(), 1);
+        let PopResult::Batch { drained } = pool.pop_batch(10) else {
+            panic!("expected batch");
+        };
+        let hashes: Vec<&str> = drained.iter().map(|tx| tx.hash.as_str()).collect();
+        assert_eq!(hashes, vec!["alice-0-fast"]);
+    }
+
+    #[test]
+    fn replace_by_fee_keeps_queued_tx_in_place() {
+        // Given: alice nonce 0(pending), 2(queued)
+        let config = TxPoolConfig {
+            capacity: 10,
+            max_account_slots: 10,
+            ..TxPoolConfig::default()
+        };
+        let mut pool = TxPool::new(config);
+        pool.insert(make_tx("alice", 0, 5, 100, "a")).unwrap();
+        pool.insert(make_tx("alice", 2, 5, 100, "old")).unwrap();
+        let heads_before = pool.global_queue.len();
+        // When: queued nonce 2 교체
+        let outcome = pool.insert(make_tx("alice", 2, 6, 200, "new")).unwrap();
+        // Then: queued에 그대로 머물고 global_queue는 변하지 않음
+        assert_eq!(outcome.sub_pool, SubPool::Queued);
+        assert_eq!(pool.queued_len(), 1);
+        assert_eq!(pool.global_queue.len(), heads_before);
+        assert_eq!(
+            pool.per_account[&Pubkey::from_name("alice")].txs[&2].hash,
+            "alice-2-new"
+        );
+    }
+
+    #[test]
+    fn canonical_block_removes_mined_and_stale_txs() {
+        // Given: alice 0,1,2(pending) + 4(queued), bob 0(pending)
+        let config = TxPoolConfig {
+            capacity: 10,
+            max_account_slots: 10,
+            ..TxPoolConfig::default()
+        };
+        let mut pool = TxPool::new(config);
+        for (nonce, tag) in [(0, "a0"), (1, "a1"), (2, "a2"), (4, "a4")] {
+            pool.insert(make_tx("alice", nonce, 5, 100, tag)).unwrap();
+        }
+        pool.insert(make_tx("bob", 0, 9, 100, "b0")).unwrap();
+        // When: alice 0,1이 포함된 블록, bob은 풀 밖의 tx로 nonce 0이 소모됨
+        let update = pool.on_canonical_block(CanonicalBlock {
+            mined: vec![
+                (Pubkey::from_name("alice"), 0),
+                (Pubkey::from_name("alice"), 1),
+            ],
+            account_nonces: HashMap::from([(Pubkey::from_name("bob"), 1)]),
+            ..CanonicalBlock::default()
+        });
+        // Then: alice 0,1은 mined, bob 0은 stale로 discarded, alice 2가 새 head
+        let hashes = |txs: &[PendingTransaction]| -> Vec<String> {
+            txs.iter().map(|tx| tx.hash.clone()).collect()
+        };
+        assert_eq!(hashes(&update.mined), vec!["alice-0-a0", "alice-1-a1"]);
+        assert_eq!(hashes(&update.discarded), vec!["bob-0-b0"]);
+        assert_eq!(
+            (pool.len(), pool.pending_len(), pool.queued_len()),
+            (2, 1, 1)
+        );
+        let PopResult::Batch { drained } = pool.pop_batch(10) else {
+            panic!("expected batch");
+        };
+        assert_eq!(hashes(&drained), vec!["alice-2-a2"]);
+    }
+
+    #[test]
+    fn canonical_block_promotes_queued_when_nonce_jumps() {
+        // Given: alice 0(pending), 3·4(queued)
+        let config = TxPoolConfig {
+            capacity: 10,
+            max_account_slots: 10,
+            ..TxPoolConfig::default()
+        };
+        let mut pool = TxPool::new(config);
+        for (nonce, tag) in [(0, "a0"), (3, "a3"), (4, "a4")] {
+            pool.insert(make_tx("alice", nonce, 5, 100, tag)).unwrap();
+        }
+        // When: alice nonce 0~2가 포함된 블록 (1, 2는 다른 경로로 전파된 tx)
+        let update = pool.on_canonical_block(CanonicalBlock {
+            mined: vec![
+                (Pubkey::from_name("alice"), 0),
+                (Pubkey::from_name("alice"), 1),
+                (Pubkey::from_name("alice"), 2),
+            ],
+            ..CanonicalBlock::default()
+        });
+        // Then: state nonce 3으로 추정되어 3, 4가 pending으로 승격
+        assert_eq!(update.mined.len(), 1);
+        assert_eq!(update.promoted, vec!["alice-3-a3", "alice-4-a4"]);
+        assert_eq!((pool.pending_len(), pool.queued_len()), (2, 0));
+        let PopResult::Batch { drained } = pool.pop_batch(1) else {
+            panic!("expected batch");
+        };
+        assert_eq!(drained[0].hash, "alice-3-a3");
+    }
+
+    #[test]
+    fn reorg_reinjects_reverted_transactions() {
+        // Given: alice 0,1을 pop_batch로 내보낸 뒤 alice 2가 pending
+        let config = TxPoolConfig {
+            capacity: 10,
+            max_account_slots: 10,
+            ..TxPoolConfig::default()
+        };
+        let mut pool = TxPool::new(config);
+        let a0 = make_tx("alice", 0, 5, 100, "a0");
+        let a1 = make_tx("alice", 1, 5, 100, "a1");
+        pool.insert(a0.clone()).unwrap();
+        pool.insert(a1.clone()).unwrap();
+        pool.pop_batch(2);
+        pool.insert(make_tx("alice", 2, 5, 100, "a2")).unwrap();
+        // When: 0, 1이 담긴 블록이 되돌려지고, 새 체인은 alice 0만 포함
+        let update = pool.on_reorg(
+            vec![a0, a1],
+            CanonicalBlock {
+                mined: Vec::new(),
+                account_nonces: HashMap::from([(Pubkey::from_name("alice"), 1)]),
+                ..CanonicalBlock::default()
+            },
+        );
+        // Then: 새 체인에 다시 포함된 0은 버리고 1만 재삽입되어 1, 2가 빈틈 없이 pending
+        assert_eq!(update.reinjected, vec!["alice-1-a1"]);
+        assert_eq!(update.promoted, vec!["alice-2-a2"]);
+        assert!(update.discarded.is_empty());
+        assert_eq!(nonces_in(&pool, "alice", SubPool::Pending), vec![1, 2]);
+        let PopResult::Batch { drained } = pool.pop_batch(1) else {
+            panic!("expected batch");
+        };
+        assert_eq!(drained[0].hash, "alice-1-a1");
+    }
+
+    #[test]
+    fn reorg_discards_reverted_transaction_superseded_at_same_nonce() {
+        // Given: alice 0을 내보낸 뒤, 같은 nonce 0의 다른 트랜잭션(가스 가격 동일)이 풀에 들어옴
+        let mut pool = TxPool::new(TxPoolConfig::default());
+        let events = pool.subscribe();
+        let a0 = make_tx("alice", 0, 5, 100, "a0");
+        pool.insert(a0.clone()).unwrap();
+        pool.pop_batch(1);
+        pool.on_canonical_block(CanonicalBlock {
+            account_nonces: HashMap::from([(Pubkey::from_name("alice"), 0)]),
+            ..CanonicalBlock::default()
+        });
+        pool.insert(make_tx("alice", 0, 5, 100, "other")).unwrap();
+        events.try_iter().for_each(drop);
+        // When: a0가 담긴 블록이 되돌려짐
+        let update = pool.on_reorg(vec![a0], CanonicalBlock::default());
+        // Then: 자리를 차지한 트랜잭션이 남고, 되돌려진 a0는 discarded로 보고되며 Discarded 이벤트가 나감
+        assert!(update.reinjected.is_empty());
+        assert_eq!(hashes_of(&update.discarded), vec!["alice-0-a0"]);
+        assert_eq!(
+            events.try_iter().collect::<Vec<_>>(),
+            vec![PoolEvent::Discarded {
+                hash: "alice-0-a0".to_string()
+            }]
+        );
+        assert_eq!(nonces_in(&pool, "alice", SubPool::Pending), vec![0]);
+    }
+
+    #[test]
+    fn canonical_block_skips_mined_nonce_without_successor() {
+        // Given: alice 0이 pending
+        let mut pool = TxPool::new(TxPoolConfig::default());
+        pool.insert(make_tx("alice", 0, 5, 100, "a0")).unwrap();
+        // When: 블록 입력에 nonce u64::MAX인 쌍이 섞여 있음
+        let update = pool.on_canonical_block(CanonicalBlock {
+            mined: vec![(Pubkey::from_name("alice"), u64::MAX)],
+            ..CanonicalBlock::default()
+        });
+        // Then: 패닉 없이 그 쌍을 건너뛰어 state nonce와 풀이 그대로
+        assert!(update.mined.is_empty() && update.discarded.is_empty());
+        assert_eq!(nonces_in(&pool, "alice", SubPool::Pending), vec![0]);
+    }
+
+    #[test]
+    fn orders_heads_by_effective_tip_under_base_fee() {
+        // Given: base fee 0에서 alice(max_fee 100, 팁 50), bob(max_fee 200, 팁 30)
+        let mut pool = TxPool::new(TxPoolConfig::default());
+        pool.insert(make_1559_tx("alice", 0, 100, 50, "a")).unwrap();
+        pool.insert(make_1559_tx("bob", 0, 200, 30, "b")).unwrap();
+        assert_eq!(
+            pool.per_account[&Pubkey::from_name("alice")].txs[&0].priority_key(0),
+            (50, 0)
+        );
+        // When: base fee가 80으로 오름 → alice 팁은 min(50, 20)=20, bob 팁은 30
+        pool.set_base_fee(80);
+        // Then: bob이 먼저 나오고, 경계를 넘은 alice head만 max_fee 집합으로 옮겨짐
+        assert_eq!(pool.global_queue.by_max_fee.len(), 1);
+        assert_eq!(drain_hashes(&mut pool, 2), vec!["bob-0-b", "alice-0-a"]);
+    }
+
+    #[test]
+    fn base_fee_rise_parks_underpriced_chain_and_fall_restores_it() {
+        // Given: alice 0(max_fee 100), 1(max_fee 50), 2(max_fee 100), bob 0(max_fee 30)
+        let mut pool = TxPool::new(TxPoolConfig::default());
+        pool.insert(make_1559_tx("alice", 0, 100, 5, "a0")).unwrap();
+        pool.insert(make_1559_tx("alice", 1, 50, 5, "a1")).unwrap();
+        pool.insert(make_1559_tx("alice", 2, 100, 5, "a2")).unwrap();
+        pool.insert(make_1559_tx("bob", 0, 30, 5, "b0")).unwrap();
+        // When: base fee 60
+        let promoted = pool.set_base_fee(60);
+        // Then: alice 1부터 뒤따르는 nonce까지 parked, bob 0도 parked
+        assert!(promoted.is_empty());
+        assert_eq!(nonces_in(&pool, "alice", SubPool::Pending), vec![0]);
+        assert_eq!(nonces_in(&pool, "alice", SubPool::Parked), vec![1, 2]);
+        assert_eq!(
+            pool.sub_pool(&Pubkey::from_name("bob"), 0),
+            Some(SubPool::Parked)
+        );
+        assert_eq!((pool.pending_len(), pool.parked_len()), (1, 3));
+        // When: base fee 60일 때 삽입된 underpriced tx는 바로 parked
+        let outcome = pool.insert(make_1559_tx("carol", 0, 10, 1, "c0")).unwrap();
+        assert_eq!(outcome.sub_pool, SubPool::Parked);
+        // When: base fee 40으로 내려감
+        let promoted = pool.set_base_fee(40);
+        // Then: alice 1·2만 pending으로 돌아오고 bob·carol은 여전히 parked
+        assert_eq!(promoted, vec!["alice-1-a1", "alice-2-a2"]);
+        assert_eq!(nonces_in(&pool, "alice", SubPool::Pending), vec![0, 1, 2]);
+        assert_eq!(pool.parked_len(), 2);
+        assert_eq!(
+            drain_hashes(&mut pool, 10),
+            vec!["alice-0-a0", "alice-1-a1", "alice-2-a2"]
+        );
+    }
+
+    #[test]
+    fn canonical_block_applies_next_base_fee() {
+        // Given: alice 0(max_fee 100), bob 0(max_fee 50)
+        let mut pool = TxPool::new(TxPoolConfig::default());
+        pool.insert(make_1559_tx("alice", 0, 100, 5, "a0")).unwrap();
+        pool.insert(make_1559_tx("alice", 1, 100, 5, "a1")).unwrap();
+        pool.insert(make_1559_tx("bob", 0, 50, 5, "b0")).unwrap();
+        // When: alice 0이 포함되고 다음 base fee가 70인 블록
+        let update = pool.on_canonical_block(CanonicalBlock {
+            mined: vec![(Pubkey::from_name("alice"), 0)],
+            base_fee: Some(70),
+            ..CanonicalBlock::default()
+        });
+        // Then: bob은 parked, alice 1만 pending
+        assert_eq!(update.mined.len(), 1);
+        assert_eq!(pool.base_fee(), 70);
+        assert_eq!(
+            pool.sub_pool(&Pubkey::from_name("bob"), 0),
+            Some(SubPool::Parked)
+        );
+        assert_eq!(drain_hashes(&mut pool, 10), vec!["alice-1-a1"]);
+    }
+
+    #[test]
+    fn eviction_takes_tail_of_worst_sender() {
+        // Given: capacity=4, alice 0·1(gas 200) + 2(gas 10), bob 0(gas 100)
+        let config = TxPoolConfig {
+            capacity: 4,
+            ..TxPoolConfig::default()
+        };
+        let mut pool = TxPool::new(config);
+        pool.insert(make_tx("alice", 0, 0, 200, "a0")).unwrap();
+        pool.insert(make_tx("alice", 1, 0, 200, "a1")).unwrap();
+        pool.insert(make_tx("alice", 2, 0, 10, "a2")).unwrap();
+        pool.insert(make_tx("bob", 0, 0, 100, "b0")).unwrap();
+        // When: carol 0(gas 150) insert
+        let outcome = pool.insert(make_tx("carol", 0, 0, 150, "c0")).unwrap();
+        // Then: alice의 꼬리(nonce 2)만 빠지고 alice 0·1 사슬은 그대로 pending
+        assert_eq!(outcome.evicted, vec!["alice-2-a2"]);
+        assert_eq!(nonces_in(&pool, "alice", SubPool::Pending), vec![0, 1]);
+        assert_eq!(pool.len(), 4);
+    }
+
+    #[test]
+    fn eviction_prefers_queued_over_pending() {
+        // Given: capacity=3, alice 0(gas 10, pending), bob 5(gas 1000, queued), carol 0(gas 20)
+        let config = TxPoolConfig {
+            capacity: 3,
+            ..TxPoolConfig::default()
+        };
+        let mut pool = TxPool::new(config);
+        pool.insert(make_tx("alice", 0, 0, 10, "a0")).unwrap();
+        pool.insert(make_tx("bob", 5, 0, 1000, "b5")).unwrap();
+        pool.insert(make_tx("carol", 0, 0, 20, "c0")).unwrap();
+        // When: dave 0(gas 15, pending 예정) insert
+        let outcome = pool.insert(make_tx("dave", 0, 0, 15, "d0")).unwrap();
+        // Then: 가격이 가장 높아도 실행할 수 없는 queued가 먼저 밀려남
+        assert_eq!(outcome.evicted, vec!["bob-5-b5"]);
+        assert_eq!(pool.queued_len(), 0);
+        // When: 또 다른 queued tx insert
+        let result = pool.insert(make_tx("erin", 3, 0, 1000, "e3"));
+        // Then: queued는 어떤 pending보다 순위가 낮아 거부
+        assert!(matches!(result, Err(TxInsertError::PoolFull)));
+    }
+
+    #[test]
+    fn eviction_never_drops_own_predecessor() {
+        // Given: capacity=2, alice 0·1(gas 10)
+        let config = TxPoolConfig {
+            capacity: 2,
+            ..TxPoolConfig::default()
+        };
+        let mut pool = TxPool::new(config);
+        pool.insert(make_tx("alice", 0, 0, 10, "a0")).unwrap();
+        pool.insert(make_tx("alice", 1, 0, 10, "a1")).unwrap();
+        // When: 훨씬 비싼 alice 2 insert
+        let result = pool.insert(make_tx("alice", 2, 0, 1000, "a2"));
+        // Then: 꼬리를 빼면 자신의 선행 nonce가 사라지므로 거부
+        assert!(matches!(result, Err(TxInsertError::PoolFull)));
+        assert_eq!(nonces_in(&pool, "alice", SubPool::Pending), vec![0, 1]);
+    }
+
+    #[test]
+    fn eviction_stays_fast_with_full_pool() {
+        // Given: capacity=10k, sender 5천 명, 고정 시드 LCG로 만든 수수료
+        let config = TxPoolConfig {
+            capacity: 10_000,
+            max_account_slots: 64,
+            ..TxPoolConfig::default()
+        };
+        let mut pool = TxPool::new(config);
+        let mut seed: u64 = 0x5eed;
+        let mut next_random = move || {
+            seed = seed
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            seed >> 33
+        };
+        let mut next_nonces: HashMap<usize, u64> = HashMap::new();
+        let started = std::time::Instant::now();
+        // When: 10만 건 insert, 1만 건마다 base fee 변경 (가끔 nonce를 건너뛰어 queued도 섞음)
+        for i in 0..100_000u64 {
+            if i % 10_000 == 0 {
+                pool.set_base_fee(next_random() % 100);
+            }
+            let sender = (next_random() % 5_000) as usize;
+            let nonce = next_nonces.entry(sender).or_default();
+            if next_random() % 10 == 0 {
+                *nonce += 1;
+            }
+            let max_fee = 50 + next_random() % 200;
+            let tip = next_random() % 50;
+            let tx = make_1559_tx(&format!("s{sender}"), *nonce, max_fee, tip, "bench");
+            *nonce += 1;
+            let _ = pool.insert(tx);
+        }
+        let elapsed = started.elapsed();
+        // Then: 용량을 넘지 않고, 인덱스가 sender 수와 맞으며, 전체 스캔 없이 시간 예산 안에 끝남
+        assert_eq!(pool.len(), 10_000);
+        assert_eq!(pool.tails.len(), pool.per_account.len());
+        assert_eq!(
+            pool.global_queue.len(),
+            pool.per_account
+                .values()
+                .filter(|queue| queue.pending > 0)
+                .count()
+        );
+        assert!(elapsed < std::time::Duration::from_secs(5), "{elapsed:?}");
+    }
+
+    #[test]
+    fn prune_expired_drops_dependents_and_frees_slots() {
+        // Given: max_age 60초, alice 0·1은 t0, alice 2·bob 0은 t30에 삽입, alice 슬롯은 가득 참
+        let config = TxPoolConfig {
+            max_account_slots: 3,
+            max_age: Duration::from_secs(60),
+            ..TxPoolConfig::default()
+        };
+        let (now, clock) = manual_clock();
+        let mut pool = TxPool::new(config).with_clock(clock);
+        pool.insert(make_tx("alice", 0, 0, 100, "a0")).unwrap();
+        pool.insert(make_tx("alice", 1, 0, 100, "a1")).unwrap();
+        advance(&now, 30);
+        pool.insert(make_tx("alice", 2, 0, 100, "a2")).unwrap();
+        pool.insert(make_tx("bob", 0, 0, 100, "b0")).unwrap();
+        // When: 정확히 60초가 지난 시점에 정리
+        let at_limit = pool.prune_expired(advance(&now, 30));
+        // Then: 나이가 max_age와 같으면 아직 만료가 아님
+        assert!(at_limit.expired.is_empty() && at_limit.dependents.is_empty());
+        // When: t70에 정리
+        let outcome = pool.prune_expired(advance(&now, 10));
+        // Then: alice 0·1은 만료, 아직 어린 alice 2는 선행 nonce가 사라져 함께 빠지고 bob은 남음
+        assert_eq!(
+            hashes_of(&outcome.expired),
+            vec!["alice-0-a0", "alice-1-a1"]
+        );
+        assert_eq!(hashes_of(&outcome.dependents), vec!["alice-2-a2"]);
+        assert_eq!(pool.len(), 1);
+        assert_eq!(nonces_in(&pool, "bob", SubPool::Pending), vec![0]);
+        // Then: 비워진 alice 슬롯에 다시 넣을 수 있음
+        pool.insert(make_tx("alice", 0, 0, 150, "again")).unwrap();
+        assert_eq!(
+            drain_hashes(&mut pool, 2),
+            vec!["alice-0-again", "bob-0-b0"]
+        );
+    }
+
+    #[test]
+    fn prune_expired_keeps_replaced_head_and_cuts_from_expired_nonce() {
+        // Given: max_age 60초, t0에 alice 0·1, t50에 alice 0을 교체하고 alice 2 삽입
+        let config = TxPoolConfig {
+            max_age: Duration::from_secs(60),
+            ..TxPoolConfig::default()
+        };
+        let (now, clock) = manual_clock();
+        let mut pool = TxPool::new(config).with_clock(clock);
+        pool.insert(make_tx("alice", 0, 0, 100, "a0")).unwrap();
+        pool.insert(make_tx("alice", 1, 0, 100, "a1")).unwrap();
+        advance(&now, 50);
+        pool.insert(make_tx("alice", 0, 0, 200, "bumped")).unwrap();
+        pool.insert(make_tx("alice", 2, 0, 100, "a2")).unwrap();
+        // When: t70에 정리
+        let outcome = pool.prune_expired(advance(&now, 20));
+        // Then: 교체로 삽입 시각이 새로 찍힌 nonce 0은 남고, 만료된 nonce 1부터 뒤가 빠짐
+        assert_eq!(hashes_of(&outcome.expired), vec!["alice-1-a1"]);
+        assert_eq!(hashes_of(&outcome.dependents), vec!["alice-2-a2"]);
+        assert_eq!(nonces_in(&pool, "alice", SubPool::Pending), vec![0]);
+        // When: t120에 다시 정리
+        let outcome = pool.prune_expired(advance(&now, 50));
+        // Then: 교체 시점부터 60초가 지난 nonce 0도 만료되어 풀이 비고 인덱스도 정리됨
+        assert_eq!(hashes_of(&outcome.expired), vec!["alice-0-bumped"]);
+        assert!(pool.is_empty());
+        assert!(pool.oldest_insertions.is_empty());
+        assert!(pool.global_queue.is_empty());
+    }
+
+    #[test]
+    fn events_follow_insert_replace_and_evict_sequence() {
+        // Given: capacity=3 풀을 구독
+        let config = TxPoolConfig {
+            capacity: 3,
+            ..TxPoolConfig::default()
+        };
+        let mut pool = TxPool::new(config);
+        let events = pool.subscribe();
+        // When: 삽입·교체·축출·거부가 섞인 시나리오 실행
+        pool.insert(make_tx("alice", 0, 0, 10, "slow")).unwrap();
+        pool.insert(make_tx("bob", 0, 0, 20, "b0")).unwrap();
+        pool.insert(make_tx("bob", 5, 0, 1000, "b5")).unwrap();
+        pool.insert(make_tx("alice", 0, 0, 15, "fast")).unwrap();
+        pool.insert(make_tx("carol", 0, 0, 30, "c0")).unwrap();
+        assert!(pool.insert(make_tx("dave", 0, 0, 5, "d0")).is_err());
+        pool.insert(make_tx("erin", 0, 0, 50, "e0")).unwrap();
+        // Then: 교체는 Replaced 뒤 Added, 축출은 Evicted 뒤 Added 순서이고 거부된 삽입은 이벤트가 없음
+        let received: Vec<PoolEvent> = events.try_iter().collect();
+        assert_eq!(
+            received,
+            vec![
+                added("alice-0-slow", SubPool::Pending),
+                added("bob-0-b0", SubPool::Pending),
+                added("bob-5-b5", SubPool::Queued),
+                PoolEvent::Replaced {
+                    hash: "alice-0-slow".to_string(),
+                    replaced_by: "alice-0-fast".to_string(),
+                },
+                added("alice-0-fast", SubPool::Pending),
+                PoolEvent::Evicted {
+                    hash: "bob-5-b5".to_string(),
+                },
+                added("carol-0-c0", SubPool::Pending),
+                PoolEvent::Evicted {
+                    hash: "alice-0-fast".to_string(),
+                },
+                added("erin-0-e0", SubPool::Pending),
+            ]
+        );
+    }
+
+    #[test]
+    fn events_report_mined_discarded_and_expired() {
+        // Given: max_age 60초, t0에 alice 0·1·2와 bob 0, t30에 alice 3과 carol 0
+        let config = TxPoolConfig {
+            max_age: Duration::from_secs(60),
+            ..TxPoolConfig::default()
+        };
+        let (now, clock) = manual_clock();
+        let mut pool = TxPool::new(config).with_clock(clock);
+        for nonce in 0..3 {
+            pool.insert(make_tx("alice", nonce, 0, 100, "a")).unwrap();
+        }
+        pool.insert(make_tx("bob", 0, 0, 100, "b")).unwrap();
+        advance(&now, 30);
+        pool.insert(make_tx("alice", 3, 0, 100, "a")).unwrap();
+        pool.insert(make_tx("carol", 0, 0, 100, "c")).unwrap();
+        let events = pool.subscribe();
+        let dropped = pool.subscribe();
+        drop(dropped);
+        // When: alice 0이 포함되고 bob nonce가 1로 오른 블록, 이어서 t70에 만료 정리
+        pool.on_canonical_block(CanonicalBlock {
+            mined: vec![(Pubkey::from_name("alice"), 0)],
+            account_nonces: HashMap::from([(Pubkey::from_name("bob"), 1)]),
+            base_fee: None,
+        });
+        pool.prune_expired(advance(&now, 40));
+        // Then: 블록 반영은 Mined·Discarded, 만료는 Expired 뒤 후속 nonce의 Discarded이며 닫힌 구독자는 빠짐
+        let hash = |hash: &str| hash.to_string();
+        let received: Vec<PoolEvent> = events.try_iter().collect();
+        assert_eq!(
+            received,
+            vec![
+                PoolEvent::Mined {
+                    hash: hash("alice-0-a")
+                },
+                PoolEvent::Discarded {
+                    hash: hash("bob-0-b")
+                },
+                PoolEvent::Expired {
+                    hash: hash("alice-1-a")
+                },
+                PoolEvent::Expired {
+                    hash: hash("alice-2-a")
+                },
+                PoolEvent::Discarded {
+                    hash: hash("alice-3-a")
+                },
+            ]
+        );
+        assert_eq!(pool.listeners.len(), 1);
+        assert_eq!(pool.len(), 1);
+    }
+
+    #[test]
+    fn local_sender_bypasses_slots_and_is_never_evicted() {
+        // Given: 공개 capacity 2·계정 슬롯 1, keeper는 로컬 sender로 예약 capacity 3
+        let config = TxPoolConfig {
+            capacity: 2,
+            max_account_slots: 1,
+            local_senders: HashSet::from([Pubkey::from_name("keeper")]),
+            local_capacity: 3,
+            ..TxPoolConfig::default()
+        };
+        let mut pool = TxPool::new(config);
+        // When: keeper가 가장 싼 가격으로 슬롯 상한을 넘겨 3건 insert
+        for nonce in 0..3 {
+            pool.insert(make_tx("keeper", nonce, 0, 1, "k")).unwrap();
+        }
+        pool.insert(make_tx("alice", 0, 0, 10, "a0")).unwrap();
+        pool.insert(make_tx("bob", 0, 0, 20, "b0")).unwrap();
+        // Then: 공개 capacity가 차도 keeper는 후보가 아니어서 가장 싼 공개 트랜잭션인 alice가 밀려남
+        let outcome = pool.insert(make_tx("carol", 0, 0, 30, "c0")).unwrap();
+        assert_eq!(outcome.evicted, vec!["alice-0-a0"]);
+        assert_eq!(pool.local_len(), 3);
+        assert_eq!(pool.len(), 5);
+        // Then: 예약 capacity가 차면 로컬도 PoolFull이고, 공개 쪽은 그대로 꼬리 축출로 돌아감
+        assert!(matches!(
+            pool.insert(make_tx("keeper", 3, 0, 1000, "k")),
+            Err(TxInsertError::PoolFull)
+        ));
+        assert!(matches!(
+            pool.insert(make_tx("dave", 0, 0, 5, "d0")),
+            Err(TxInsertError::PoolFull)
+        ));
+        // When: keeper 트랜잭션이 블록에 포함됨
+        pool.on_canonical_block(CanonicalBlock {
+            mined: vec![(Pubkey::from_name("keeper"), 0)],
+            ..CanonicalBlock::default()
+        });
+        // Then: 예약 자리가 비어 다시 넣을 수 있음
+        assert_eq!(pool.local_len(), 2);
+        pool.insert(make_tx("keeper", 3, 0, 1, "k")).unwrap();
+    }
+
+    #[test]
+    fn local_sender_is_exempt_from_ttl() {
+        // Given: max_age 60초, t0에 keeper(로컬)와 alice가 각각 삽입
+        let config = TxPoolConfig {
+            max_age: Duration::from_secs(60),
+            local_senders: HashSet::from([Pubkey::from_name("keeper")]),
+            ..TxPoolConfig::default()
+        };
+        let (now, clock) = manual_clock();
+        let mut pool = TxPool::new(config).with_clock(clock);
+        pool.insert(make_tx("keeper", 0, 0, 10, "k0")).unwrap();
+        pool.insert(make_tx("alice", 0, 0, 10, "a0")).unwrap();
+        // When: t100에 만료 정리
+        let outcome = pool.prune_expired(advance(&now, 100));
+        // Then: 공개 트랜잭션만 만료되고 keeper는 남음
+        assert_eq!(hashes_of(&outcome.expired), vec!["alice-0-a0"]);
+        assert_eq!(nonces_in(&pool, "keeper", SubPool::Pending), vec![0]);
+        assert_eq!(pool.local_len(), 1);
+    }
+
+    // 차등 테스트용 참조 모델입니다. 인덱스·캐시 없이 sender 큐만 두고, 호출마다 전체를 훑어 같은 규칙을 계산합니다.
+    #[derive(Clone)]
+    struct ModelPool {
+        config: TxPoolConfig,
+        txs: BTreeMap<Pubkey, BTreeMap<u64, (PendingTransaction, Instant)>>,
+        state_nonces: HashMap<Pubkey, u64>,
+        base_fee: u64,
+    }
+
+    impl ModelPool {
+        fn new(config: TxPoolConfig) -> Self {
+            Self {
+                config,
+                txs: BTreeMap::new(),
+                state_nonces: HashMap::new(),
+                base_fee: 0,
+            }
+        }
+
+        fn state_nonce(&self, sender: &Pubkey) -> u64 {
+            self.state_nonces.get(sender).copied().unwrap_or(0)
+        }
+
+        fn is_local(&self, sender: &Pubkey) -> bool {
+            self.config.local_senders.contains(sender)
+        }
+
+        // sender의 트랜잭션을 nonce 순으로 sub-pool과 함께 돌려줍니다.
+        // state nonce부터 빈틈이 생기면 그 뒤는 queued, 빈틈 전에 base fee 미달을 만나면 그 뒤는 parked입니다.
+        fn sub_pools(&self, sender: &Pubkey) -> Vec<(SubPool, &PendingTransaction)> {
+            let mut expected = self.state_nonce(sender);
+            let (mut gap, mut underpriced) = (false, false);
+            let mut result = Vec::new();
+            for (tx, _) in self.txs.get(sender).into_iter().flat_map(BTreeMap::values) {
+                gap |= tx.nonce != expected;
+                underpriced |= tx.max_fee_per_gas < self.base_fee;
+                expected = tx.nonce + 1;
+                let sub_pool = if gap {
+                    SubPool::Queued
+                } else if underpriced {
+                    SubPool::Parked
+                } else {
+                    SubPool::Pending
+                };
+                result.push((sub_pool, tx));
+            }
+            result
+        }
+
+        // `(sender, nonce, sub-pool, hash)`를 sender·nonce 순으로 나열한 전체 상태입니다.
+        fn contents(&self) -> Vec<(Pubkey, u64, SubPool, String)> {
+            self.txs
+                .keys()
+                .flat_map(|sender| self.sub_pools(sender))
+                .map(|(sub_pool, tx)| (tx.sender, tx.nonce, sub_pool, tx.hash.clone()))
+                .collect()
+        }
+
+        fn pending_hashes(&self) -> HashSet<String> {
+            self.contents()
+                .into_iter()
+                .filter(|(_, _, sub_pool, _)| *sub_pool == SubPool::Pending)
+                .map(|(_, _, _, hash)| hash)
+                .collect()
+        }
+
+        // `before` 이후 새로 pending이 된 hash를 정렬해 돌려줍니다.
+        fn promoted_since(&self, before: &HashSet<String>) -> Vec<String> {
+            let mut promoted: Vec<String> =
+                self.pending_hashes().difference(before).cloned().collect();
+            promoted.sort();
+            promoted
+        }
+
+        fn len_where(&self, local: bool) -> usize {
+            self.txs
+                .iter()
+                .filter(|(sender, _)| self.is_local(sender) == local)
+                .map(|(_, queue)| queue.len())
+                .sum()
+        }
+
+        fn eviction_rank(&self, tx: &PendingTransaction, sub_pool: SubPool) -> EvictionRank {
+            (
+                sub_pool.retention_rank(),
+                tx.effective_tip(self.base_fee).unwrap_or(0),
+                tx.max_fee_per_gas,
+                tx.priority,
+            )
+        }
+
+        fn insert(
+            &mut self,
+            tx: PendingTransaction,
+            now: Instant,
+        ) -> Result<InsertOutcome, TxInsertError> {
+            let (sender, nonce) = (tx.sender, tx.nonce);
+            let state_nonce = self.state_nonce(&sender);
+            if nonce < state_nonce {
+                return Err(TxInsertError::NonceTooLow {
+                    sender,
+                    nonce,
+                    state_nonce,
+                });
+            }
+            let before = self.pending_hashes();
+
+            if let Some((existing, _)) = self.txs.get(&sender).and_then(|queue| queue.get(&nonce)) {
+                if existing.hash == tx.hash {
+                    return Err(TxInsertError::DuplicateNonce { sender, nonce });
+                }
+                let bump = |old: u64| {
+                    old + (u128::from(old) * u128::from(self.config.price_bump) / 100) as u64
+                };
+                let (min_max_fee, min_tip) = (
+                    bump(existing.max_fee_per_gas),
+                    bump(existing.max_priority_fee_per_gas),
+                );
+                if tx.max_fee_per_gas < min_max_fee || tx.max_priority_fee_per_gas < min_tip {
+                    return Err(TxInsertError::ReplacementUnderpriced {
+                        sender,
+                        nonce,
+                        min_max_fee_per_gas: min_max_fee,
+                        min_max_priority_fee_per_gas: min_tip,
+                    });
+                }
+                let hash = tx.hash.clone();
+                let replaced = self
+                    .txs
+                    .get_mut(&sender)
+                    .unwrap()
+                    .insert(nonce, (tx, now))
+                    .map(|(old, _)| old);
+                let mut promoted = self.promoted_since(&before);
+                promoted.retain(|promoted_hash| *promoted_hash != hash);
+                return Ok(InsertOutcome {
+                    sub_pool: self.sub_pool_of(&sender, nonce),
+                    promoted,
+                    replaced,
+                    evicted: Vec::new(),
+                });
+            }
+
+            let mut evicted = Vec::new();
+            if self.is_local(&sender) {
+                if self.len_where(true) >= self.config.local_capacity {
+                    return Err(TxInsertError::PoolFull);
+                }
+            } else {
+                if self.txs.get(&sender).map_or(0, BTreeMap::len) >= self.config.max_account_slots {
+                    return Err(TxInsertError::AccountLimitReached { sender });
+                }
+                if self.len_where(false) >= self.config.capacity {
+                    // 새 트랜잭션이 들어갈 sub-pool은 실제로 넣어 본 모델에서 읽습니다.
+                    let mut trial = self.clone();
+                    trial
+                        .txs
+                        .entry(sender)
+                        .or_default()
+                        .insert(nonce, (tx.clone(), now));
+                    let incoming_rank = self.eviction_rank(&tx, trial.sub_pool_of(&sender, nonce));
+                    // 공개 sender마다 최고 nonce 하나만 후보이며, 새 트랜잭션의 선행 nonce는 뺍니다.
+                    let victim = self
+                        .txs
+                        .keys()
+                        .filter(|candidate| !self.is_local(candidate))
+                        .filter_map(|candidate| {
+                            let (sub_pool, tail) = self.sub_pools(candidate).pop()?;
+                            let rank = self.eviction_rank(tail, sub_pool);
+                            let predecessor = tail.sender == sender && tail.nonce < nonce;
+                            (rank < incoming_rank && !predecessor).then_some((
+                                rank,
+                                tail.sender,
+                                tail.nonce,
+                            ))
+                        })
+                        .min()
+                        .ok_or(TxInsertError::PoolFull)?;
+                    let (_, victim_sender, victim_nonce) = victim;
+                    evicted.push(self.remove(&victim_sender, victim_nonce).hash);
+                }
+            }
+
+            let hash = tx.hash.clone();
+            self.txs.entry(sender).or_default().insert(nonce, (tx, now));
+            let mut promoted = self.promoted_since(&before);
+            promoted.retain(|promoted_hash| *promoted_hash != hash);
+            Ok(InsertOutcome {
+                sub_pool: self.sub_pool_of(&sender, nonce),
+                promoted,
+                replaced: None,
+                evicted,
+            })
+        }
+
+        fn sub_pool_of(&self, sender: &Pubkey, nonce: u64) -> SubPool {
+            self.sub_pools(sender)
+                .into_iter()
+                .find(|(_, tx)| tx.nonce == nonce)
+                .map(|(sub_pool, _)| sub_pool)
+                .unwrap()
+        }
+
+        fn remove(&mut self, sender: &Pubkey, nonce: u64) -> PendingTransaction {
+            let queue = self.txs.get_mut(sender).unwrap();
+            let (tx, _) = queue.remove(&nonce).unwrap();
+            if queue.is_empty() {
+                self.txs.remove(sender);
+            }
+            tx
+        }
+
+        // pending head 중 `(effective tip, priority, 낮은 nonce, sender)`가 가장 큰 것을 하나씩 꺼낸 순서입니다.
+        fn pop_batch(&mut self, limit: usize) -> Vec<String> {
+            let mut drained = Vec::new();
+            while drained.len() < limit {
+                let best = self
+                    .txs
+                    .keys()
+                    .filter_map(|sender| {
+                        let (sub_pool, head) = *self.sub_pools(sender).first()?;
+                        (sub_pool == SubPool::Pending).then_some(head)
+                    })
+                    .max_by_key(|head| {
+                        (
+                            head.effective_tip(self.base_fee).unwrap_or(0),
+                            head.priority,
+                            Reverse(head.nonce),
+                            head.sender,
+                        )
+                    })
+                    .map(|head| (head.sender, head.nonce));
+                let Some((sender, nonce)) = best else {
+                    break;
+                };
+                self.state_nonces.insert(sender, nonce + 1);
+                drained.push(self.remove(&sender, nonce).hash);
+            }
+            drained
+        }
+
+        fn set_base_fee(&mut self, base_fee: u64) -> Vec<String> {
+            let before = self.pending_hashes();
+            self.base_fee = base_fee;
+            self.promoted_since(&before)
+        }
+
+        // `(mined, discarded, promoted)` hash 목록입니다.
+        fn on_canonical_block(
+            &mut self,
+            mined: &[(Pubkey, u64)],
+            base_fee: Option<u64>,
+        ) -> (Vec<String>, Vec<String>, Vec<String>) {
+            let before = self.pending_hashes();
+            let mut next_nonces: BTreeMap<Pubkey, u64> = BTreeMap::new();
+            for &(sender, nonce) in mined {
+                let next = next_nonces
+                    .entry(sender)
+                    .or_insert(self.state_nonce(&sender));
+                *next = (*next).max(nonce + 1);
+            }
+            let (mut mined_hashes, mut discarded) = (Vec::new(), Vec::new());
+            for (sender, state_nonce) in next_nonces {
+                self.state_nonces.insert(sender, state_nonce);
+                let stale: Vec<u64> = self
+                    .txs
+                    .get(&sender)
+                    .into_iter()
+                    .flat_map(|queue| queue.range(..state_nonce).map(|(&nonce, _)| nonce))
+                    .collect();
+                for nonce in stale {
+                    let tx = self.remove(&sender, nonce);
+                    if mined.contains(&(sender, nonce)) {
+                        mined_hashes.push(tx.hash);
+                    } else {
+                        discarded.push(tx.hash);
+                    }
+                }
+            }
+            if let Some(base_fee) = base_fee {
+                self.base_fee = base_fee;
+            }
+            (mined_hashes, discarded, self.promoted_since(&before))
+        }
+
+        // `(expired, dependents)` hash 목록이며 각각 정렬되어 있습니다.
+        fn prune_expired(&mut self, now: Instant) -> (Vec<String>, Vec<String>) {
+            let (mut expired, mut dependents) = (Vec::new(), Vec::new());
+            let Some(cutoff) = now.checked_sub(self.config.max_age) else {
+                return (expired, dependents);
+            };
+            let senders: Vec<Pubkey> = self
+                .txs
+                .keys()
+                .copied()
+                .filter(|sender| !self.is_local(sender))
+                .collect();
+            for sender in senders {
+                let Some(first_expired) = self.txs[&sender]
+                    .values()
+                    .find(|(_, inserted_at)| *inserted_at < cutoff)
+                    .map(|(tx, _)| tx.nonce)
+                else {
+                    continue;
+                };
+                let dropped: Vec<(u64, Instant)> = self.txs[&sender]
+                    .range(first_expired..)
+                    .map(|(&nonce, &(_, inserted_at))| (nonce, inserted_at))
+                    .collect();
+                for (nonce, inserted_at) in dropped {
+                    let tx = self.remove(&sender, nonce);
+                    if inserted_at < cutoff {
+                        expired.push(tx.hash);
+                    } else {
+                        dependents.push(tx.hash);
+                    }
+                }
+            }
+            expired.sort();
+            dependents.sort();
+            (expired, dependents)
+        }
+    }
+
+    fn pool_contents(pool: &TxPool) -> Vec<(Pubkey, u64, SubPool, String)> {
+        let mut contents: Vec<_> = pool
+            .transactions()
+            .map(|(sub_pool, tx)| (tx.sender, tx.nonce, sub_pool, tx.hash.clone()))
+            .collect();
+        contents.sort_by_key(|&(sender, nonce, _, _)| (sender, nonce));
+        contents
+    }
+
+    fn sorted<T: Ord>(mut hashes: Vec<T>) -> Vec<T> {
+        hashes.sort();
+        hashes
+    }
+
+    // 같은 시드의 무작위 연산 열을 풀과 참조 모델에 똑같이 적용하고, 매 단계 결과·전체 상태·실행 순서·내부 불변식을 비교합니다.
+    fn run_against_model(config: TxPoolConfig, senders: &[&str], seed: u64, steps: usize) {
+        let (now, clock) = manual_clock();
+        let mut pool = TxPool::new(config.clone()).with_clock(clock);
+        let mut model = ModelPool::new(config);
+        let mut seed = seed;
+        let mut next_random = move || {
+            seed = seed
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            seed >> 33
+        };
+
+        for step in 0..steps {
+            let at = advance(&now, next_random() % 8);
+            let context = format!("seed {seed}, step {step}");
+            let name = senders[(next_random() % senders.len() as u64) as usize];
+            let sender = Pubkey::from_name(name);
+            let state_nonce = model.state_nonce(&sender);
+            match next_random() % 100 {
+                0..=54 => {
+                    // 가끔 이미 있는 nonce를 그대로 재제출하거나 수수료를 올려 교체하고, 가끔 실행된 nonce를 보냅니다.
+                    let existing = model
+                        .txs
+                        .get(&sender)
+                        .and_then(|queue| queue.values().nth((next_random() % 4) as usize))
+                        .map(|(tx, _)| tx.clone());
+                    let tx = match (existing, next_random() % 8) {
+                        (Some(existing), 0) => existing,
+                        (Some(existing), 1 | 2) => PendingTransaction {
+                            hash: format!("{existing_hash}+{step}", existing_hash = existing.hash),
+                            max_fee_per_gas: existing.max_fee_per_gas * 11 / 10 + next_random() % 3,
+                            max_priority_fee_per_gas: existing.max_priority_fee_per_gas * 11 / 10
+                                + next_random() % 3,
+                            ..existing
+                        },
+                        _ => {
+                            let nonce = if state_nonce > 0 && next_random() % 20 == 0 {
+                                state_nonce - 1
+                            } else {
+                                state_nonce + next_random() % 5
+                            };
+                            PendingTransaction {
+                                priority: (next_random() % 3).into(),
+                                ..make_1559_tx(
+                                    name,
+                                    nonce,
+                                    1 + next_random() % 60,
+                                    next_random() % 30,
+                                    &step.to_string(),
+                                )
+                            }
+                        }
+                    };
+                    // 모델은 승격 목록을 hash 순으로 돌려주므로 풀 쪽도 정렬해 비교합니다.
+                    let actual = pool.insert(tx.clone()).map(|mut outcome| {
+                        outcome.promoted.sort();
+                        outcome
+                    });
+                    let expected = model.insert(tx, at);
+                    assert_eq!(
+                        format!("{actual:?}"),
+                        format!("{expected:?}"),
+                        "insert, {context}"
+                    );
+                }
+                55..=69 => {
+                    let limit = (next_random() % 4) as usize;
+                    let expected = sorted(model.pop_batch(limit));
+                    let actual = match pool.pop_batch(limit) {
+                        PopResult::Batch { drained } => {
+                            assert!(
+                                drained
+                                    .windows(2)
+                                    .all(|pair| pair[0].priority_key(pool.base_fee())
+                                        >= pair[1].priority_key(pool.base_fee())),
+                                "pop_batch 정렬, {context}"
+                            );
+                            sorted(drained.into_iter().map(|tx| tx.hash).collect())
+                        }
+                        PopResult::Empty => Vec::new(),
+                    };
+                    assert_eq!(actual, expected, "pop_batch, {context}");
+                }
+                70..=79 => {
+                    let base_fee = next_random() % 40;
+                    assert_eq!(
+                        sorted(pool.set_base_fee(base_fee)),
+                        model.set_base_fee(base_fee),
+                        "set_base_fee, {context}"
+                    );
+                }
+                80..=89 => {
+                    let mined: Vec<(Pubkey, u64)> = (0..next_random() % 3)
+                        .map(|_| (sender, state_nonce + next_random() % 3))
+                        .collect();
+                    let base_fee = (next_random() % 2 == 0).then(|| next_random() % 40);
+                    let (mined_hashes, discarded, promoted) =
+                        model.on_canonical_block(&mined, base_fee);
+                    let update = pool.on_canonical_block(CanonicalBlock {
+                        mined,
+                        base_fee,
+                        ..CanonicalBlock::default()
+                    });
+                    assert_eq!(hashes_of(&update.mined), mined_hashes, "mined, {context}");
+                    assert_eq!(
+                        hashes_of(&update.discarded),
+                        discarded,
+                        "discarded, {context}"
+                    );
+                    assert_eq!(sorted(update.promoted), promoted, "promoted, {context}");
+                }
+                _ => {
+                    let outcome = pool.prune_expired(at);
+                    let (expired, dependents) = model.prune_expired(at);
+                    assert_eq!(
+                        sorted(hashes_of(&outcome.expired)),
+                        expired,
+                        "expired, {context}"
+                    );
+                    assert_eq!(
+                        sorted(hashes_of(&outcome.dependents)),
+                        dependents,
+                        "dependents, {context}"
+                    );
+                }
+            }
+
+            pool.assert_invariants();
+            assert_eq!(
+                pool_contents(&pool),
+                model.contents(),
+                "contents, {context}"
+            );
+            let best: Vec<String> = pool.best_transactions().map(|tx| tx.hash).collect();
+            assert_eq!(
+                best,
+                model.clone().pop_batch(usize::MAX),
+                "best order, {context}"
+            );
+        }
+    }
+
+    #[test]
+    fn matches_reference_model_under_random_operations() {
+        // Given: 넉넉한 capacity라 축출 없이 nonce 빈틈·교체·base fee·블록·만료만 섞이는 설정
+        let config = TxPoolConfig {
+            capacity: 1_000,
+            max_account_slots: 8,
+            max_age: Duration::from_secs(60),
+            ..TxPoolConfig::default()
+        };
+        // When/Then: 시드마다 무작위 연산 열을 적용하며 매 단계 모델과 비교
+        for seed in 0..20 {
+            run_against_model(config.clone(), &["s0", "s1", "s2", "s3"], seed, 400);
+        }
+    }
+
+    #[test]
+    fn matches_reference_model_with_eviction_and_local_senders() {
+        // Given: capacity가 작아 축출·슬롯 제한이 자주 일어나고, 로컬 sender가 예약 자리를 쓰는 설정
+        let config = TxPoolConfig {
+            capacity: 10,
+            max_account_slots: 4,
+            max_age: Duration::from_secs(60),
+            local_senders: HashSet::from([Pubkey::from_name("keeper")]),
+            local_capacity: 5,
+            ..TxPoolConfig::default()
+        };
+        // When/Then: 시드마다 무작위 연산 열을 적용하며 매 단계 모델과 비교
+        for seed in 0..20 {
+            run_against_model(
+                config.clone(),
+                &["s0", "s1", "s2", "s3", "s4", "keeper"],
+                seed,
+                400,
+            );
+        }
     }
 }
@@ -0,0 +1,423 @@
+//! TxPool 스냅숏 저장·복원입니다. 한 줄에 레코드 하나를 쓰고 줄마다 체크섬을 붙여 손상된 줄만 골라 버립니다.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+use std::str;
+
+use crate::{
+    CanonicalBlock, InsertOutcome, PendingTransaction, Pubkey, SenderQueue, TxInsertError, TxPool,
+    TxPoolConfig,
+};
+
+/// save_to·load_from에서 파일 자체를 쓸 수 없을 때의 에러입니다. 개별 항목 손상은 LoadReport로 보고합니다.
+#[derive(Debug)]
+pub enum PersistError {
+    /// 파일 읽기·쓰기 실패입니다.
+    Io(io::Error),
+    /// 첫 줄이 스냅숏 헤더가 아닌 경우입니다.
+    InvalidHeader,
+    /// 헤더는 맞지만 이 빌드가 읽을 수 없는 형식 버전인 경우입니다.
+    UnsupportedVersion { found: u32 },
+}
+
+impl From<io::Error> for PersistError {
+    fn from(error: io::Error) -> Self {
+        PersistError::Io(error)
+    }
+}
+
+/// 스냅숏 복원 결과입니다.
+#[derive(Debug, Default)]
+pub struct LoadReport {
+    /// 다시 풀에 들어간 hash 목록이며 sender·nonce 오름차순입니다.
+    pub restored: Vec<String>,
+    /// 체크섬이 맞지 않거나 UTF-8이 아니거나 해석할 수 없어 버린 줄 번호(1부터)입니다.
+    pub corrupted: Vec<usize>,
+    /// 읽기는 했지만 insert 검사(nonce, validator, 슬롯·capacity)를 통과하지 못한 트랜잭션과 사유입니다.
+    pub rejected: Vec<(PendingTransaction, TxInsertError)>,
+}
+
+impl LoadReport {
+    pub(crate) fn record(
+        &mut self,
+        tx: PendingTransaction,
+        result: Result<InsertOutcome, TxInsertError>,
+    ) {
+        match result {
+            Ok(_) => self.restored.push(tx.hash),
+            Err(error) => self.rejected.push((tx, error)),
+        }
+    }
+}
+
+impl TxPool {
+    /// 풀의 모든 트랜잭션과 base fee, sender별 state nonce를 버전 헤더가 붙은 스냅숏 파일로 저장합니다.
+    /// state nonce는 pop_batch로 내보낸 만큼 앞당겨진 값이라, 저장하지 않으면 복원 후 이미 내보낸 nonce가 다시 들어옵니다.
+    /// `<파일 이름>.tmp` 임시 파일에 쓰고 fsync한 뒤 rename하고 디렉터리까지 fsync하므로 중간에 멈추거나 전원이 나가도 기존 스냅숏이 깨지지 않습니다.
+    /// 삽입 시각은 프로세스 밖에서 의미가 없어 저장하지 않으며, 복원 시점부터 TTL을 다시 잽니다.
+    pub fn save_to(&self, path: impl AsRef<Path>) -> Result<(), PersistError> {
+        write_snapshot(path.as_ref(), &[self])
+    }
+
+    /// 스냅숏 파일에서 새 풀을 만듭니다. 검증기·nonce 조회기가 필요하면 먼저 풀을 구성한 뒤 restore_from을 쓰면 됩니다.
+    pub fn load_from(
+        path: impl AsRef<Path>,
+        config: TxPoolConfig,
+    ) -> Result<(Self, LoadReport), PersistError> {
+        let mut pool = Self::new(config);
+        let report = pool.restore_from(path)?;
+        Ok((pool, report))
+    }
+
+    /// 스냅숏의 트랜잭션을 일반 insert 경로로 다시 넣습니다.
+    /// 손상된 줄은 건너뛰고, 그사이 온체인 nonce가 올랐거나 검증기를 통과하지 못하는 항목은 rejected로 보고합니다.
+    pub fn restore_from(&mut self, path: impl AsRef<Path>) -> Result<LoadReport, PersistError> {
+        let snapshot = read_snapshot(path.as_ref())?;
+        self.restore_state(snapshot.base_fee, snapshot.state_nonces);
+        let mut report = LoadReport {
+            corrupted: snapshot.corrupted,
+            ..LoadReport::default()
+        };
+        for tx in snapshot.txs {
+            let result = self.insert(tx.clone());
+            report.record(tx, result);
+        }
+        Ok(report)
+    }
+
+    /// 트랜잭션을 넣기 전에 base fee와 state nonce를 맞춰, sub-pool이 저장 당시와 같게 나뉘게 합니다.
+    /// 그사이 체인이 더 나아갔을 수 있으므로 state nonce는 조회기 값보다 높을 때만 올리며, 블록 반영과 같은 경로를 씁니다.
+    pub(crate) fn restore_state(
+        &mut self,
+        base_fee: Option<u64>,
+        state_nonces: impl IntoIterator<Item = (Pubkey, u64)>,
+    ) {
+        let account_nonces: HashMap<Pubkey, u64> = state_nonces
+            .into_iter()
+            .filter(|&(sender, nonce)| nonce > self.state_nonce(sender))
+            .collect();
+        self.on_canonical_block(CanonicalBlock {
+            mined: Vec::new(),
+            account_nonces,
+            base_fee,
+        });
+    }
+}
+
+/// 스냅숏 파일에서 읽은 내용입니다. 트랜잭션은 불필요한 queued→pending 이동을 줄이도록 sender·nonce 오름차순입니다.
+pub(crate) struct Snapshot {
+    pub(crate) base_fee: Option<u64>,
+    pub(crate) state_nonces: Vec<(Pubkey, u64)>,
+    pub(crate) txs: Vec<PendingTransaction>,
+    pub(crate) corrupted: Vec<usize>,
+}
+
+/// 여러 풀(TxPoolHandle의 shard)을 한 스냅숏으로 씁니다. base fee는 모든 풀이 같으므로 첫 풀의 값을 씁니다.
+pub(crate) fn write_snapshot(path: &Path, pools: &[&TxPool]) -> Result<(), PersistError> {
+    let mut contents = format!("{SNAPSHOT_MAGIC} v{SNAPSHOT_VERSION}\n");
+    if let Some(pool) = pools.first() {
+        push_record(&mut contents, &format!("base_fee {}", pool.base_fee));
+    }
+
+    let mut state_nonces: Vec<(&Pubkey, &u64)> =
+        pools.iter().flat_map(|pool| &pool.state_nonces).collect();
+    state_nonces.sort();
+    for (sender, nonce) in state_nonces {
+        push_record(&mut contents, &format!("state_nonce {sender} {nonce}"));
+    }
+
+    let mut queues: Vec<(&Pubkey, &SenderQueue)> =
+        pools.iter().flat_map(|pool| &pool.per_account).collect();
+    queues.sort_by_key(|&(sender, _)| sender);
+    for (_, queue) in queues {
+        for tx in queue.txs.values() {
+            push_record(&mut contents, &encode_tx(tx));
+        }
+    }
+
+    // with_extension은 a.snapshot과 a.json이 같은 a.tmp를 쓰게 하므로, 파일 이름 전체 뒤에 붙입니다.
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let mut file = File::create(&temporary)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&temporary, path)?;
+    // rename은 디렉터리 항목을 바꾸므로 디렉터리까지 fsync해야 전원이 나가도 새 이름이 남습니다.
+    // 상대 경로 파일 이름만 주면 parent가 빈 경로라 현재 디렉터리를 씁니다.
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    File::open(directory)?.sync_all()?;
+    Ok(())
+}
+
+pub(crate) fn read_snapshot(path: &Path) -> Result<Snapshot, PersistError> {
+    // 줄 단위로 UTF-8을 해석해, 깨진 바이트가 섞인 줄만 손상으로 보고 나머지 줄은 살립니다.
+    let contents = fs::read(path)?;
+    let contents = contents.strip_suffix(b"\n").unwrap_or(&contents);
+    let mut lines = contents
+        .split(|&byte| byte == b'\n')
+        .map(|line| str::from_utf8(line).map(|line| line.strip_suffix('\r').unwrap_or(line)));
+    let version = lines
+        .next()
+        .and_then(|header| header.ok())
+        .and_then(|header| header.strip_prefix(SNAPSHOT_MAGIC))
+        .and_then(|rest| rest.strip_prefix(" v"))
+        .and_then(|version| version.parse::<u32>().ok())
+        .ok_or(PersistError::InvalidHeader)?;
+    if version != SNAPSHOT_VERSION {
+        return Err(PersistError::UnsupportedVersion { found: version });
+    }
+
+    let mut snapshot = Snapshot {
+        base_fee: None,
+        state_nonces: Vec::new(),
+        txs: Vec::new(),
+        corrupted: Vec::new(),
+    };
+    // 헤더가 1번 줄이므로 레코드는 2번 줄부터입니다.
+    for (line_number, line) in (2..).zip(lines) {
+        match line.ok().and_then(decode_record) {
+            Some(Record::BaseFee(fee)) => snapshot.base_fee = Some(fee),
+            Some(Record::StateNonce(sender, nonce)) => snapshot.state_nonces.push((sender, nonce)),
+            Some(Record::Tx(tx)) => snapshot.txs.push(tx),
+            None => snapshot.corrupted.push(line_number),
+        }
+    }
+    snapshot.txs.sort_by_key(|tx| (tx.sender, tx.nonce));
+    Ok(snapshot)
+}
+
+/// 스냅숏 파일 첫 줄은 `txpool-snapshot v2` 형태입니다.
+const SNAPSHOT_MAGIC: &str = "txpool-snapshot";
+/// 레코드 필드를 바꾸면 올려야 하는 형식 버전입니다. v2에서 state_nonce 레코드가 추가되었습니다.
+const SNAPSHOT_VERSION: u32 = 2;
+
+/// 스냅숏 헤더 뒤의 한 줄입니다.
+enum Record {
+    BaseFee(u64),
+    StateNonce(Pubkey, u64),
+    Tx(PendingTransaction),
+}
+
+/// 레코드 본문 뒤에 FNV-1a 체크섬을 붙여 한 줄로 씁니다. 줄마다 검사하므로 손상된 줄만 골라 버릴 수 있습니다.
+fn push_record(contents: &mut String, body: &str) {
+    contents.push_str(&format!("{body} {:016x}\n", fnv1a(body.as_bytes())));
+}
+
+/// 체크섬이 맞고 필드를 모두 해석할 수 있을 때만 레코드를 돌려줍니다.
+fn decode_record(line: &str) -> Option<Record> {
+    let (body, checksum) = line.rsplit_once(' ')?;
+    if u64::from_str_radix(checksum, 16).ok()? != fnv1a(body.as_bytes()) {
+        return None;
+    }
+    let mut fields = body.split(' ');
+    let record = match fields.next()? {
+        "base_fee" => Record::BaseFee(fields.next()?.parse().ok()?),
+        "state_nonce" => {
+            Record::StateNonce(fields.next()?.parse().ok()?, fields.next()?.parse().ok()?)
+        }
+        "tx" => Record::Tx(PendingTransaction {
+            hash: decode_hex(fields.next()?)?,
+            sender: fields.next()?.parse().ok()?,
+            nonce: fields.next()?.parse().ok()?,
+            max_fee_per_gas: fields.next()?.parse().ok()?,
+            max_priority_fee_per_gas: fields.next()?.parse().ok()?,
+            priority: fields.next()?.parse().ok()?,
+            gas_limit: fields.next()?.parse().ok()?,
+            value: fields.next()?.parse().ok()?,
+        }),
+        _ => return None,
+    };
+    fields.next().is_none().then_some(record)
+}
+
+/// hash는 공백·줄바꿈이 섞여도 줄 형식이 깨지지 않도록 16진수로 씁니다.
+fn encode_tx(tx: &PendingTransaction) -> String {
+    let hash: String = tx.hash.bytes().map(|byte| format!("{byte:02x}")).collect();
+    format!(
+        "tx {hash} {} {} {} {} {} {} {}",
+        tx.sender,
+        tx.nonce,
+        tx.max_fee_per_gas,
+        tx.max_priority_fee_per_gas,
+        tx.priority,
+        tx.gas_limit,
+        tx.value
+    )
+}
+
+fn decode_hex(hex: &str) -> Option<String> {
+    // 길이가 홀수면 마지막 get이 None이 되어 전체가 None입니다.
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|start| u8::from_str_radix(hex.get(start..start + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    String::from_utf8(bytes).ok()
+}
+
+/// 64비트 FNV-1a 해시입니다. 표준 Hasher와 달리 버전·플랫폼이 바뀌어도 값이 같아 디스크 형식에 쓸 수 있습니다.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SubPool;
+    use crate::tests::*;
+
+    #[test]
+    fn save_and_load_round_trip_keeps_sub_pools() {
+        // Given: base fee 50에서 pending·parked·queued가 섞인 풀, 공백이 든 hash 포함
+        let mut pool = TxPool::new(TxPoolConfig::default());
+        pool.set_base_fee(50);
+        pool.insert(make_1559_tx("alice", 0, 100, 10, "a 0"))
+            .unwrap();
+        pool.insert(make_1559_tx("alice", 1, 40, 10, "a1")).unwrap();
+        pool.insert(make_1559_tx("bob", 3, 100, 10, "b3")).unwrap();
+        let path = snapshot_path("round-trip");
+        // When: 저장 후 새 풀로 불러옴
+        pool.save_to(&path).unwrap();
+        let (restored, report) = TxPool::load_from(&path, TxPoolConfig::default()).unwrap();
+        fs::remove_file(&path).unwrap();
+        // Then: 모든 항목이 같은 sub-pool과 base fee로 돌아옴
+        assert_eq!(
+            report.restored,
+            vec!["alice-0-a 0", "alice-1-a1", "bob-3-b3"]
+        );
+        assert!(report.corrupted.is_empty() && report.rejected.is_empty());
+        assert_eq!(restored.base_fee(), 50);
+        assert_eq!(
+            restored.sub_pool(&Pubkey::from_name("alice"), 0),
+            Some(SubPool::Pending)
+        );
+        assert_eq!(
+            restored.sub_pool(&Pubkey::from_name("alice"), 1),
+            Some(SubPool::Parked)
+        );
+        assert_eq!(
+            restored.sub_pool(&Pubkey::from_name("bob"), 3),
+            Some(SubPool::Queued)
+        );
+        assert_eq!(
+            restored.per_account[&Pubkey::from_name("alice")].txs[&0],
+            pool.per_account[&Pubkey::from_name("alice")].txs[&0]
+        );
+    }
+
+    #[test]
+    fn load_drops_corrupted_and_stale_entries() {
+        // Given: alice 0·1, bob 0을 저장한 뒤 bob 줄 한 글자를 바꿈
+        let mut pool = TxPool::new(TxPoolConfig::default());
+        pool.insert(make_tx("alice", 0, 0, 100, "a0")).unwrap();
+        pool.insert(make_tx("alice", 1, 0, 100, "a1")).unwrap();
+        pool.insert(make_tx("bob", 0, 0, 100, "b0")).unwrap();
+        let path = snapshot_path("corrupted");
+        pool.save_to(&path).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        let bob = Pubkey::from_name("bob").to_string();
+        let tampered: Vec<String> = contents
+            .lines()
+            .map(|line| {
+                if line.contains(&bob) {
+                    line.replacen(" 100 ", " 999 ", 1)
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect();
+        fs::write(&path, tampered.join("\n")).unwrap();
+        // When: 재시작 사이 alice 0이 실행되어 온체인 nonce가 1인 상태로 복원
+        let mut reloaded = TxPool::with_nonce_provider(
+            TxPoolConfig::default(),
+            HashMap::from([(Pubkey::from_name("alice"), 1)]),
+        );
+        let report = reloaded.restore_from(&path).unwrap();
+        // Then: bob 줄(헤더·base fee·state nonce 2줄 다음 7번 줄)은 손상으로, alice 0은 NonceTooLow로 빠지고 alice 1만 복원
+        assert_eq!(report.corrupted, vec![7]);
+        assert_eq!(report.restored, vec!["alice-1-a1"]);
+        assert!(matches!(
+            report.rejected.as_slice(),
+            [(tx, TxInsertError::NonceTooLow { state_nonce: 1, .. })] if tx.hash == "alice-0-a0"
+        ));
+        assert_eq!(
+            reloaded.sub_pool(&Pubkey::from_name("alice"), 1),
+            Some(SubPool::Pending)
+        );
+        // When: 다른 버전 헤더
+        fs::write(&path, contents.replacen("v2", "v1", 1)).unwrap();
+        let unsupported = TxPool::load_from(&path, TxPoolConfig::default());
+        fs::remove_file(&path).unwrap();
+        // Then: 형식을 모르므로 항목을 읽지 않고 에러
+        assert!(matches!(
+            unsupported,
+            Err(PersistError::UnsupportedVersion { found: 1 })
+        ));
+    }
+
+    #[test]
+    fn load_drops_non_utf8_line_and_keeps_the_rest() {
+        // Given: alice 0, bob 0을 저장한 뒤 bob 트랜잭션 줄에 UTF-8이 아닌 바이트를 끼워 넣음
+        let mut pool = TxPool::new(TxPoolConfig::default());
+        pool.insert(make_tx("alice", 0, 0, 100, "a0")).unwrap();
+        pool.insert(make_tx("bob", 0, 0, 100, "b0")).unwrap();
+        let path = snapshot_path("non-utf8");
+        pool.save_to(&path).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        let bob = Pubkey::from_name("bob").to_string();
+        let mut bob_line = 0;
+        let mut tampered = Vec::new();
+        for (line_number, line) in (1..).zip(contents.lines()) {
+            tampered.extend_from_slice(line.as_bytes());
+            if line.starts_with("tx ") && line.contains(&bob) {
+                bob_line = line_number;
+                tampered.extend_from_slice(&[0xff, 0xfe]);
+            }
+            tampered.push(b'\n');
+        }
+        fs::write(&path, tampered).unwrap();
+        // When
+        let (reloaded, report) = TxPool::load_from(&path, TxPoolConfig::default()).unwrap();
+        fs::remove_file(&path).unwrap();
+        // Then: 깨진 줄만 손상으로 보고하고 alice 0은 그대로 복원
+        assert_eq!(report.corrupted, vec![bob_line]);
+        assert_eq!(report.restored, vec!["alice-0-a0"]);
+        assert_eq!(reloaded.len(), 1);
+    }
+
+    #[test]
+    fn save_after_pop_batch_keeps_advanced_state_nonce() {
+        // Given: bob의 유일한 0과 alice 0·1·2 중 0을 pop_batch로 내보낸 풀
+        let mut pool = TxPool::new(TxPoolConfig::default());
+        for nonce in 0..3 {
+            pool.insert(make_tx("alice", nonce, 10, 100, "a")).unwrap();
+        }
+        pool.insert(make_tx("bob", 0, 0, 200, "b0")).unwrap();
+        assert_eq!(drain_hashes(&mut pool, 2), vec!["bob-0-b0", "alice-0-a"]);
+        let path = snapshot_path("after-pop");
+        pool.save_to(&path).unwrap();
+        // When: 조회기 없는 새 풀로 불러옴
+        let (mut restored, report) = TxPool::load_from(&path, TxPoolConfig::default()).unwrap();
+        fs::remove_file(&path).unwrap();
+        // Then: 남은 alice 1·2는 queued가 아니라 pending이고, 임시 파일도 남지 않음
+        assert!(report.corrupted.is_empty() && report.rejected.is_empty());
+        assert_eq!(
+            restored.sub_pool(&Pubkey::from_name("alice"), 1),
+            Some(SubPool::Pending)
+        );
+        assert_eq!(restored.pending_len(), 2);
+        assert!(!std::path::PathBuf::from(format!("{}.tmp", path.display())).exists());
+        restored.assert_invariants();
+        // Then: 이미 내보낸 bob 0은 다시 들어오지 못함
+        assert!(matches!(
+            restored.insert(make_tx("bob", 0, 0, 100, "b0-again")),
+            Err(TxInsertError::NonceTooLow { state_nonce: 1, .. })
+        ));
+    }
+}
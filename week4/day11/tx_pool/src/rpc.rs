@@ -0,0 +1,790 @@
+//! TxPoolHandle 앞에 두는 JSON-RPC 2.0 HTTP 서버입니다. (`rpc` feature)
+//!
+//! 지원 메서드:
+//! - `eth_sendRawTransaction([raw])`: `encode_raw_transaction` 형식의 16진수 문자열을 받아 삽입하고 hash를 돌려줍니다.
+//! - `txpool_status([])`: `{"pending": "0x..", "queued": "0x.."}` (parked는 queued에 합산, reth와 동일)
+//! - `txpool_content([])`: `{"pending": {sender: {nonce: tx}}, "queued": {...}}`
+//! - `txpool_inspect([])`: content와 같은 모양이며 tx 대신 한 줄 요약 문자열을 담습니다.
+
+use std::io::{self, BufRead, BufReader, Read, Take, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::num::ParseIntError;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use serde_json::{Map, Value, json};
+
+use crate::persist::fnv1a;
+use crate::{PendingTransaction, Pubkey, SubPool, TxInsertError, TxPoolHandle};
+
+/// 원시 트랜잭션 바이트 길이입니다.
+/// sender(32) | nonce(8) | max_fee_per_gas(8) | max_priority_fee_per_gas(8) | gas_limit(8) | value(16) | priority(16), 모두 big-endian.
+pub const RAW_TRANSACTION_LEN: usize = 96;
+
+/// 요청 본문 기본 상한입니다. (geth와 같은 5 MiB) 이보다 큰 Content-Length는 본문을 읽지 않고 413으로 거절합니다.
+pub const DEFAULT_MAX_BODY_BYTES: usize = 5 * 1024 * 1024;
+
+/// 연결 하나의 읽기·쓰기 기본 제한 시간입니다. 아무것도 보내지 않는 클라이언트가 스레드를 계속 붙잡지 못하게 합니다.
+pub const DEFAULT_IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 요청 줄과 헤더를 합친 최대 바이트 수입니다. 넘으면 431로 거절합니다.
+pub const MAX_HEADER_BYTES: u64 = 64 * 1024;
+
+/// 요청 하나의 최대 헤더 줄 수입니다. 넘으면 431로 거절합니다.
+pub const MAX_HEADERS: usize = 100;
+
+/// 동시에 처리하는 연결 수 기본 상한입니다. 가득 차면 새 연결에 503으로 답하고 바로 닫습니다.
+pub const DEFAULT_MAX_CONNECTIONS: usize = 256;
+
+/// accept가 실패했을 때 다시 받기 전에 쉬는 시간입니다. fd가 바닥난 동안(EMFILE) accept 루프가 CPU를 태우지 않게 합니다.
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(50);
+
+/// JSON-RPC 2.0 표준 에러 코드입니다.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// TxInsertError를 서버 정의 에러 코드(-32000..-32099)로 옮긴 값입니다. 변형마다 코드가 달라 스크립트가 분기할 수 있습니다.
+pub fn error_code(error: &TxInsertError) -> i64 {
+    match error {
+        TxInsertError::DuplicateNonce { .. } => -32010,
+        TxInsertError::AccountLimitReached { .. } => -32011,
+        TxInsertError::PoolFull => -32012,
+        TxInsertError::NonceTooLow { .. } => -32013,
+        TxInsertError::ReplacementUnderpriced { .. } => -32014,
+        TxInsertError::Underpriced { .. } => -32015,
+        TxInsertError::InsufficientFunds { .. } => -32016,
+    }
+}
+
+/// 트랜잭션을 `eth_sendRawTransaction`에 넣을 `0x` 접두 16진수 문자열로 만듭니다. hash는 담지 않습니다.
+pub fn encode_raw_transaction(tx: &PendingTransaction) -> String {
+    let mut raw = Vec::with_capacity(RAW_TRANSACTION_LEN);
+    raw.extend_from_slice(tx.sender.as_bytes());
+    raw.extend_from_slice(&tx.nonce.to_be_bytes());
+    raw.extend_from_slice(&tx.max_fee_per_gas.to_be_bytes());
+    raw.extend_from_slice(&tx.max_priority_fee_per_gas.to_be_bytes());
+    raw.extend_from_slice(&tx.gas_limit.to_be_bytes());
+    raw.extend_from_slice(&tx.value.to_be_bytes());
+    raw.extend_from_slice(&tx.priority.to_be_bytes());
+    let hex: String = raw.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("0x{hex}")
+}
+
+/// `encode_raw_transaction` 형식을 되돌립니다. hash는 원시 바이트의 FNV-1a 값 `0x` + 16자리 16진수입니다.
+pub fn decode_raw_transaction(raw: &str) -> Option<PendingTransaction> {
+    let hex = raw.strip_prefix("0x").unwrap_or(raw);
+    if hex.len() != RAW_TRANSACTION_LEN * 2 {
+        return None;
+    }
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|start| u8::from_str_radix(hex.get(start..start + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+
+    let (sender, rest) = bytes.split_first_chunk::<32>()?;
+    let (nonce, rest) = rest.split_first_chunk::<8>()?;
+    let (max_fee, rest) = rest.split_first_chunk::<8>()?;
+    let (tip, rest) = rest.split_first_chunk::<8>()?;
+    let (gas_limit, rest) = rest.split_first_chunk::<8>()?;
+    let (value, rest) = rest.split_first_chunk::<16>()?;
+    let (priority, _) = rest.split_first_chunk::<16>()?;
+    Some(PendingTransaction {
+        hash: format!("0x{:016x}", fnv1a(&bytes)),
+        sender: Pubkey::from(*sender),
+        nonce: u64::from_be_bytes(*nonce),
+        max_fee_per_gas: u64::from_be_bytes(*max_fee),
+        max_priority_fee_per_gas: u64::from_be_bytes(*tip),
+        gas_limit: u64::from_be_bytes(*gas_limit),
+        value: u128::from_be_bytes(*value),
+        priority: u128::from_be_bytes(*priority),
+    })
+}
+
+/// 요청 본문 하나(단건 또는 배치)를 처리해 응답 본문을 돌려줍니다. 알림(id 없는 요청)만 있으면 None입니다.
+/// 소켓 없이 호출할 수 있어 서버 로직을 그대로 테스트할 때도 씁니다.
+pub fn handle_request(pool: &TxPoolHandle, body: &str) -> Option<String> {
+    let request: Value = match serde_json::from_str(body) {
+        Ok(request) => request,
+        Err(error) => {
+            return Some(
+                error_response(Value::Null, PARSE_ERROR, &error.to_string(), None).to_string(),
+            );
+        }
+    };
+    let response = match request {
+        Value::Array(batch) if batch.is_empty() => Some(error_response(
+            Value::Null,
+            INVALID_REQUEST,
+            "빈 배치 요청입니다",
+            None,
+        )),
+        Value::Array(batch) => {
+            let responses: Vec<Value> = batch
+                .into_iter()
+                .filter_map(|call| dispatch(pool, call))
+                .collect();
+            (!responses.is_empty()).then_some(Value::Array(responses))
+        }
+        call => dispatch(pool, call),
+    };
+    response.map(|response| response.to_string())
+}
+
+/// 요청 객체 하나를 처리합니다. id가 없는 알림이면 실행만 하고 None을 돌려줍니다.
+fn dispatch(pool: &TxPoolHandle, call: Value) -> Option<Value> {
+    let Value::Object(call) = call else {
+        return Some(error_response(
+            Value::Null,
+            INVALID_REQUEST,
+            "요청은 객체여야 합니다",
+            None,
+        ));
+    };
+    let id = call.get("id").cloned();
+    if call.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+        return Some(error_response(
+            id.unwrap_or(Value::Null),
+            INVALID_REQUEST,
+            "jsonrpc는 \"2.0\"이어야 합니다",
+            None,
+        ));
+    }
+    let Some(method) = call.get("method").and_then(Value::as_str) else {
+        return Some(error_response(
+            id.unwrap_or(Value::Null),
+            INVALID_REQUEST,
+            "method가 없습니다",
+            None,
+        ));
+    };
+    let params = call
+        .get("params")
+        .cloned()
+        .unwrap_or(Value::Array(Vec::new()));
+
+    let result = match method {
+        "eth_sendRawTransaction" => send_raw_transaction(pool, &params),
+        "txpool_status" => {
+            let (pending, parked, queued) = pool.sub_pool_lens();
+            Ok(json!({
+                "pending": quantity(pending as u128),
+                "queued": quantity((parked + queued) as u128),
+            }))
+        }
+        "txpool_content" => Ok(content(pool, tx_object)),
+        "txpool_inspect" => Ok(content(pool, |tx| Value::String(summary(tx)))),
+        _ => Err(error_response(
+            Value::Null,
+            METHOD_NOT_FOUND,
+            &format!("지원하지 않는 메서드: {method}"),
+            None,
+        )),
+    };
+
+    let id = id?;
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(mut error) => {
+            error["id"] = id;
+            error
+        }
+    })
+}
+
+fn send_raw_transaction(pool: &TxPoolHandle, params: &Value) -> Result<Value, Value> {
+    let invalid = |message: &str| error_response(Value::Null, INVALID_PARAMS, message, None);
+    let raw = params
+        .get(0)
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid("params[0]에 원시 트랜잭션 16진수 문자열이 필요합니다"))?;
+    let tx = decode_raw_transaction(raw)
+        .ok_or_else(|| invalid("원시 트랜잭션 형식이 올바르지 않습니다"))?;
+    let hash = tx.hash.clone();
+    match pool.insert(tx) {
+        Ok(_) => Ok(Value::String(hash)),
+        Err(error) => Err(insert_error(&error)),
+    }
+}
+
+/// TxInsertError를 코드·메시지와 변형별 필드를 담은 data로 옮깁니다.
+fn insert_error(error: &TxInsertError) -> Value {
+    let (message, data) = match error {
+        TxInsertError::DuplicateNonce { sender, nonce } => (
+            "already known",
+            json!({ "kind": "DuplicateNonce", "sender": sender.to_string(), "nonce": nonce }),
+        ),
+        TxInsertError::AccountLimitReached { sender } => (
+            "account slot limit reached",
+            json!({ "kind": "AccountLimitReached", "sender": sender.to_string() }),
+        ),
+        TxInsertError::PoolFull => ("txpool is full", json!({ "kind": "PoolFull" })),
+        TxInsertError::NonceTooLow {
+            sender,
+            nonce,
+            state_nonce,
+        } => (
+            "nonce too low",
+            json!({
+                "kind": "NonceTooLow",
+                "sender": sender.to_string(),
+                "nonce": nonce,
+                "stateNonce": state_nonce,
+            }),
+        ),
+        TxInsertError::ReplacementUnderpriced {
+            sender,
+            nonce,
+            min_max_fee_per_gas,
+            min_max_priority_fee_per_gas,
+        } => (
+            "replacement transaction underpriced",
+            json!({
+                "kind": "ReplacementUnderpriced",
+                "sender": sender.to_string(),
+                "nonce": nonce,
+                "minMaxFeePerGas": min_max_fee_per_gas,
+                "minMaxPriorityFeePerGas": min_max_priority_fee_per_gas,
+            }),
+        ),
+        TxInsertError::Underpriced {
+            sender,
+            nonce,
+            min_max_priority_fee_per_gas,
+        } => (
+            "transaction underpriced",
+            json!({
+                "kind": "Underpriced",
+                "sender": sender.to_string(),
+                "nonce": nonce,
+                "minMaxPriorityFeePerGas": min_max_priority_fee_per_gas,
+            }),
+        ),
+        TxInsertError::InsufficientFunds {
+            sender,
+            nonce,
+            cost,
+            balance,
+        } => (
+            "insufficient funds for gas * price + value",
+            json!({
+                "kind": "InsufficientFunds",
+                "sender": sender.to_string(),
+                "nonce": nonce,
+                // u128은 JSON 숫자로 정확히 담기지 않을 수 있어 16진수 quantity로 보냅니다.
+                "cost": quantity(*cost),
+                "balance": quantity(*balance),
+            }),
+        ),
+    };
+    error_response(Value::Null, error_code(error), message, Some(data))
+}
+
+fn error_response(id: Value, code: i64, message: &str, data: Option<Value>) -> Value {
+    let mut error = json!({ "code": code, "message": message });
+    if let Some(data) = data {
+        error["data"] = data;
+    }
+    json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
+
+/// geth와 같은 `0x` 접두 16진수 quantity 표기입니다.
+fn quantity(value: u128) -> String {
+    format!("0x{value:x}")
+}
+
+/// pending과 그 밖(parked·queued)을 나눠 `{sender: {nonce: render(tx)}}`로 묶습니다.
+fn content(pool: &TxPoolHandle, render: impl Fn(&PendingTransaction) -> Value) -> Value {
+    let mut pending = Map::new();
+    let mut queued = Map::new();
+    for (sub_pool, tx) in pool.transactions() {
+        let bucket = match sub_pool {
+            SubPool::Pending => &mut pending,
+            SubPool::Parked | SubPool::Queued => &mut queued,
+        };
+        let by_nonce = bucket
+            .entry(tx.sender.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        by_nonce[tx.nonce.to_string()] = render(&tx);
+    }
+    json!({ "pending": pending, "queued": queued })
+}
+
+fn tx_object(tx: &PendingTransaction) -> Value {
+    json!({
+        "hash": tx.hash,
+        "from": tx.sender.to_string(),
+        "nonce": quantity(tx.nonce.into()),
+        "maxFeePerGas": quantity(tx.max_fee_per_gas.into()),
+        "maxPriorityFeePerGas": quantity(tx.max_priority_fee_per_gas.into()),
+        "gas": quantity(tx.gas_limit.into()),
+        "value": quantity(tx.value),
+        "priority": quantity(tx.priority),
+    })
+}
+
+/// txpool_inspect 한 줄 요약입니다. 수신자 필드가 없으므로 geth 형식에서 `to:` 부분만 뺍니다.
+fn summary(tx: &PendingTransaction) -> String {
+    format!(
+        "{} wei + {} gas × {} wei",
+        tx.value, tx.gas_limit, tx.max_fee_per_gas
+    )
+}
+
+/// localhost HTTP로 JSON-RPC 요청을 받는 서버입니다. 연결마다 스레드 하나를 쓰고 응답 후 연결을 닫습니다.
+/// 동시 연결(스레드) 수는 `max_connections`로 묶입니다.
+pub struct RpcServer {
+    listener: TcpListener,
+    pool: TxPoolHandle,
+    limits: ConnectionLimits,
+    max_connections: usize,
+}
+
+/// 연결마다 적용하는 본문 크기·I/O 시간 제한입니다.
+#[derive(Clone, Copy)]
+struct ConnectionLimits {
+    max_body_bytes: usize,
+    timeout: Duration,
+}
+
+impl RpcServer {
+    /// 주소에 바인딩합니다. 포트 0을 주면 빈 포트를 골라 `local_addr`로 알려 줍니다.
+    /// 본문 상한, I/O 제한 시간, 동시 연결 상한은 `DEFAULT_MAX_BODY_BYTES`, `DEFAULT_IO_TIMEOUT`,
+    /// `DEFAULT_MAX_CONNECTIONS`로 시작합니다.
+    pub fn bind(addr: impl ToSocketAddrs, pool: TxPoolHandle) -> io::Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(addr)?,
+            pool,
+            limits: ConnectionLimits {
+                max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+                timeout: DEFAULT_IO_TIMEOUT,
+            },
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+        })
+    }
+
+    /// 요청 본문 상한(바이트)을 바꿉니다.
+    pub fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.limits.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    /// 연결의 읽기·쓰기 제한 시간을 바꿉니다. 0이면 소켓이 거부하므로 호출자가 양수를 넘겨야 합니다.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.limits.timeout = timeout;
+        self
+    }
+
+    /// 동시에 처리할 연결 수 상한을 바꿉니다.
+    pub fn with_max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = max_connections;
+        self
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// 현재 스레드에서 연결을 계속 받습니다. 개별 연결의 I/O 에러는 그 연결만 끊고 서버는 계속 돕니다.
+    /// accept 실패(EMFILE, ECONNABORTED 등)도 그 연결 하나의 문제로 보고 잠깐 쉰 뒤 다음 연결을 받습니다.
+    pub fn serve(self) -> io::Result<()> {
+        let active = Arc::new(AtomicUsize::new(0));
+        for stream in self.listener.incoming() {
+            let Ok(mut stream) = stream else {
+                thread::sleep(ACCEPT_BACKOFF);
+                continue;
+            };
+            // 상한에 닿았으면 스레드를 만들지 않고 503으로 답합니다.
+            let Some(slot) = ConnectionSlot::acquire(&active, self.max_connections) else {
+                let _ = stream.set_write_timeout(Some(self.limits.timeout));
+                let _ = write_response(&mut stream, "503 Service Unavailable", "");
+                continue;
+            };
+            let pool = self.pool.clone();
+            let limits = self.limits;
+            thread::spawn(move || {
+                let _slot = slot;
+                // 클라이언트가 중간에 끊었거나 제한 시간을 넘긴 경우 등은 응답할 곳이 없으므로 무시합니다.
+                let _ = serve_connection(stream, &pool, limits);
+            });
+        }
+        Ok(())
+    }
+
+    /// 백그라운드 스레드에서 serve를 돌립니다.
+    pub fn spawn(self) -> JoinHandle<io::Result<()>> {
+        thread::spawn(move || self.serve())
+    }
+}
+
+/// 처리 중인 연결 하나의 자리입니다. 연결 스레드가 끝나거나 패닉해도 drop에서 자리를 돌려줍니다.
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    fn acquire(active: &Arc<AtomicUsize>, max_connections: usize) -> Option<Self> {
+        active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                (count < max_connections).then_some(count + 1)
+            })
+            .ok()
+            .map(|_| Self(Arc::clone(active)))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// 요청 줄과 헤더에서 읽어 둔 값입니다.
+struct RequestHead {
+    request_line: String,
+    content_length: Option<Result<usize, ParseIntError>>,
+}
+
+/// 요청 줄과 헤더를 `MAX_HEADER_BYTES`·`MAX_HEADERS` 안에서 읽습니다. 둘 중 하나라도 넘으면 None입니다.
+fn read_head(reader: &mut impl BufRead) -> io::Result<Option<RequestHead>> {
+    let mut head = reader.take(MAX_HEADER_BYTES);
+    let mut request_line = String::new();
+    if !read_head_line(&mut head, &mut request_line)? {
+        return Ok(None);
+    }
+
+    let mut content_length = None;
+    let mut headers = 0;
+    loop {
+        let mut header = String::new();
+        if !read_head_line(&mut head, &mut header)? {
+            return Ok(None);
+        }
+        if header.trim_end().is_empty() {
+            break;
+        }
+        headers += 1;
+        if headers > MAX_HEADERS {
+            return Ok(None);
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            content_length = Some(value.trim().parse::<usize>());
+        }
+    }
+    Ok(Some(RequestHead {
+        request_line,
+        content_length,
+    }))
+}
+
+/// 한 줄을 읽고, 줄바꿈 전에 바이트 한도가 바닥났으면 false를 돌려줍니다.
+/// 한도가 남은 채 줄바꿈 없이 끝나면 클라이언트가 연결을 닫은 것이므로 그 줄까지를 헤더의 끝으로 봅니다.
+fn read_head_line(head: &mut Take<impl BufRead>, line: &mut String) -> io::Result<bool> {
+    head.read_line(line)?;
+    Ok(line.ends_with('\n') || head.limit() > 0)
+}
+
+/// HTTP 요청 하나를 읽어 POST 본문을 handle_request에 넘기고 응답을 씁니다.
+/// 요청 줄·헤더가 `MAX_HEADER_BYTES`나 `MAX_HEADERS`를 넘으면 431,
+/// Content-Length가 없거나 숫자가 아니면 400, 상한을 넘으면 본문을 읽지 않고 413으로 답합니다.
+fn serve_connection(
+    stream: TcpStream,
+    pool: &TxPoolHandle,
+    limits: ConnectionLimits,
+) -> io::Result<()> {
+    stream.set_read_timeout(Some(limits.timeout))?;
+    stream.set_write_timeout(Some(limits.timeout))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let head = read_head(&mut reader)?;
+
+    let mut stream = stream;
+    let Some(RequestHead {
+        request_line,
+        content_length,
+    }) = head
+    else {
+        return write_response(&mut stream, "431 Request Header Fields Too Large", "");
+    };
+    if !request_line.starts_with("POST ") {
+        return write_response(&mut stream, "405 Method Not Allowed", "");
+    }
+    let content_length = match content_length {
+        Some(Ok(length)) => length,
+        None | Some(Err(_)) => return write_response(&mut stream, "400 Bad Request", ""),
+    };
+    if content_length > limits.max_body_bytes {
+        return write_response(&mut stream, "413 Payload Too Large", "");
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    let body = String::from_utf8_lossy(&body);
+    match handle_request(pool, &body) {
+        Some(response) => write_response(&mut stream, "200 OK", &response),
+        None => write_response(&mut stream, "204 No Content", ""),
+    }
+}
+
+fn write_response(stream: &mut TcpStream, status: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TxPoolConfig;
+
+    fn raw_tx(sender: &str, nonce: u64, max_fee: u64, tip: u64) -> String {
+        encode_raw_transaction(&PendingTransaction {
+            hash: String::new(),
+            sender: Pubkey::from_name(sender),
+            nonce,
+            max_fee_per_gas: max_fee,
+            max_priority_fee_per_gas: tip,
+            priority: 0,
+            gas_limit: 21_000,
+            value: 1,
+        })
+    }
+
+    fn call(pool: &TxPoolHandle, method: &str, params: Value) -> Value {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response =
+            handle_request(pool, &request.to_string()).expect("id가 있으면 응답이 와야 합니다");
+        serde_json::from_str(&response).unwrap()
+    }
+
+    /// 서버에 원시 HTTP 요청을 보내고, 서버가 연결을 닫을 때까지 받은 응답 전체를 돌려줍니다.
+    fn send_http(addr: SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn send_raw_transaction_then_inspect_pool() {
+        // Given: 빈 풀
+        let pool = TxPoolHandle::new(TxPoolConfig::default(), 4);
+        // When: alice 0(pending), alice 2(queued)를 원시 트랜잭션으로 제출
+        let sent = call(
+            &pool,
+            "eth_sendRawTransaction",
+            json!([raw_tx("alice", 0, 100, 5)]),
+        );
+        call(
+            &pool,
+            "eth_sendRawTransaction",
+            json!([raw_tx("alice", 2, 100, 5)]),
+        );
+        // Then: 돌려준 hash로 pending에 보이고, status·content·inspect가 같은 내용을 보여줌
+        let hash = sent["result"].as_str().unwrap();
+        assert_eq!(
+            hash,
+            decode_raw_transaction(&raw_tx("alice", 0, 100, 5))
+                .unwrap()
+                .hash
+        );
+        assert_eq!(
+            call(&pool, "txpool_status", json!([]))["result"],
+            json!({ "pending": "0x1", "queued": "0x1" })
+        );
+        let alice = Pubkey::from_name("alice").to_string();
+        let content = call(&pool, "txpool_content", json!([]));
+        assert_eq!(content["result"]["pending"][&alice]["0"]["hash"], hash);
+        assert_eq!(
+            content["result"]["queued"][&alice]["2"]["maxFeePerGas"],
+            "0x64"
+        );
+        let inspect = call(&pool, "txpool_inspect", json!([]));
+        assert_eq!(
+            inspect["result"]["pending"][&alice]["0"],
+            "1 wei + 21000 gas × 100 wei"
+        );
+    }
+
+    #[test]
+    fn errors_carry_codes_and_insert_details() {
+        // Given: alice 0이 이미 들어 있는 풀
+        let pool = TxPoolHandle::new(TxPoolConfig::default(), 1);
+        call(
+            &pool,
+            "eth_sendRawTransaction",
+            json!([raw_tx("alice", 0, 100, 10)]),
+        );
+        // When: 인상 폭이 모자란 교체
+        let underpriced = call(
+            &pool,
+            "eth_sendRawTransaction",
+            json!([raw_tx("alice", 0, 105, 10)]),
+        );
+        // Then: 변형별 코드와 교체에 필요한 최소 수수료가 data로 옴
+        assert_eq!(underpriced["error"]["code"], -32014);
+        assert_eq!(
+            underpriced["error"]["data"]["kind"],
+            "ReplacementUnderpriced"
+        );
+        assert_eq!(underpriced["error"]["data"]["minMaxFeePerGas"], 110);
+        assert_eq!(underpriced["id"], 1);
+        // Then: 표준 에러도 JSON-RPC 코드로 옴
+        assert_eq!(
+            call(&pool, "eth_sendRawTransaction", json!(["0x12"]))["error"]["code"],
+            INVALID_PARAMS
+        );
+        assert_eq!(
+            call(&pool, "eth_mining", json!([]))["error"]["code"],
+            METHOD_NOT_FOUND
+        );
+        let parse: Value = serde_json::from_str(&handle_request(&pool, "{").unwrap()).unwrap();
+        assert_eq!(parse["error"]["code"], PARSE_ERROR);
+        // Then: 알림만 담긴 요청은 응답하지 않음
+        let notification = json!({ "jsonrpc": "2.0", "method": "txpool_status" });
+        assert_eq!(handle_request(&pool, &notification.to_string()), None);
+    }
+
+    #[test]
+    fn serves_json_rpc_over_localhost_http() {
+        // Given: 빈 포트에 띄운 서버
+        let pool = TxPoolHandle::new(TxPoolConfig::default(), 2);
+        let server = RpcServer::bind("127.0.0.1:0", pool.clone()).unwrap();
+        let addr = server.local_addr().unwrap();
+        server.spawn();
+        // When: HTTP POST로 배치 요청(제출 + 상태 조회)
+        let body = json!([
+            { "jsonrpc": "2.0", "id": 1, "method": "eth_sendRawTransaction", "params": [raw_tx("bob", 0, 100, 1)] },
+            { "jsonrpc": "2.0", "id": 2, "method": "txpool_status" },
+        ])
+        .to_string();
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "POST / HTTP/1.1\r\nHost: {addr}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        // Then: 200 응답 본문에 두 결과가 순서대로 있고, 같은 핸들을 쓰는 풀에도 반영됨
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        assert!(head.starts_with("HTTP/1.1 200 OK"));
+        let results: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(results[0]["id"], 1);
+        assert!(results[0]["result"].is_string());
+        assert_eq!(results[1]["result"]["pending"], "0x1");
+        assert_eq!(pool.pending_len(), 1);
+    }
+
+    #[test]
+    fn rejects_missing_invalid_and_oversized_content_length() {
+        // Given: 본문 상한 64바이트인 서버
+        let server = RpcServer::bind("127.0.0.1:0", TxPoolHandle::new(TxPoolConfig::default(), 1))
+            .unwrap()
+            .with_max_body_bytes(64);
+        let addr = server.local_addr().unwrap();
+        server.spawn();
+        let status = |request: &str| {
+            let response = send_http(addr, request);
+            response.lines().next().unwrap_or_default().to_string()
+        };
+        // When/Then: Content-Length가 없거나 숫자가 아니면 400
+        assert_eq!(
+            status("POST / HTTP/1.1\r\n\r\n{}"),
+            "HTTP/1.1 400 Bad Request"
+        );
+        assert_eq!(
+            status("POST / HTTP/1.1\r\nContent-Length: -1\r\n\r\n{}"),
+            "HTTP/1.1 400 Bad Request"
+        );
+        // When/Then: 상한을 넘는 Content-Length는 본문을 기다리지 않고 413
+        assert_eq!(
+            status("POST / HTTP/1.1\r\nContent-Length: 18446744073709551615\r\n\r\n"),
+            "HTTP/1.1 413 Payload Too Large"
+        );
+        assert_eq!(
+            status("POST / HTTP/1.1\r\nContent-Length: 65\r\n\r\n"),
+            "HTTP/1.1 413 Payload Too Large"
+        );
+    }
+
+    #[test]
+    fn rejects_oversized_head_and_too_many_headers() {
+        // Given: 기본 한도로 띄운 서버
+        let server =
+            RpcServer::bind("127.0.0.1:0", TxPoolHandle::new(TxPoolConfig::default(), 1)).unwrap();
+        let addr = server.local_addr().unwrap();
+        server.spawn();
+        let status = |request: &str| {
+            let response = send_http(addr, request);
+            response.lines().next().unwrap_or_default().to_string()
+        };
+        // When/Then: 줄바꿈 없이 MAX_HEADER_BYTES를 채운 요청 줄은 끝까지 기다리지 않고 431
+        let long_line = "P".repeat(MAX_HEADER_BYTES as usize);
+        assert_eq!(
+            status(&long_line),
+            "HTTP/1.1 431 Request Header Fields Too Large"
+        );
+        // When/Then: 헤더가 MAX_HEADERS개를 넘으면 431
+        let mut request = "POST / HTTP/1.1\r\n".to_string();
+        for index in 0..=MAX_HEADERS {
+            request.push_str(&format!("X-Header-{index}: 1\r\n"));
+        }
+        assert_eq!(
+            status(&request),
+            "HTTP/1.1 431 Request Header Fields Too Large"
+        );
+    }
+
+    #[test]
+    fn answers_503_when_connection_limit_is_reached() {
+        // Given: 동시 연결 1개로 묶은 서버에 아무것도 보내지 않는 연결 하나가 붙어 있음
+        let server = RpcServer::bind("127.0.0.1:0", TxPoolHandle::new(TxPoolConfig::default(), 1))
+            .unwrap()
+            .with_max_connections(1);
+        let addr = server.local_addr().unwrap();
+        server.spawn();
+        let idle = TcpStream::connect(addr).unwrap();
+        // When: 다음 연결
+        let response = send_http(addr, "");
+        // Then: 스레드를 만들지 않고 503
+        assert!(response.starts_with("HTTP/1.1 503 Service Unavailable"));
+        // When: 붙어 있던 연결이 끊겨 자리가 돌아옴
+        drop(idle);
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": "txpool_status" }).to_string();
+        let request = format!(
+            "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        // Then: 연결 스레드가 끝나면 다시 받음
+        let accepted = (0..50).any(|_| {
+            let response = send_http(addr, &request);
+            if response.starts_with("HTTP/1.1 200 OK") {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(20));
+            false
+        });
+        assert!(accepted);
+    }
+
+    #[test]
+    fn idle_connection_is_closed_after_timeout() {
+        // Given: 제한 시간 100ms인 서버
+        let server = RpcServer::bind("127.0.0.1:0", TxPoolHandle::new(TxPoolConfig::default(), 1))
+            .unwrap()
+            .with_timeout(Duration::from_millis(100));
+        let addr = server.local_addr().unwrap();
+        server.spawn();
+        // When: 본문 길이만 알리고 본문을 보내지 않음
+        let response = send_http(addr, "POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\n");
+        // Then: 서버가 기다리다 응답 없이 연결을 닫음
+        assert!(response.is_empty());
+    }
+}
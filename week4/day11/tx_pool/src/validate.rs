@@ -0,0 +1,212 @@
+//! 계정 상태 조회와 insert 전 트랜잭션 검사입니다.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::{PendingTransaction, Pubkey, TxInsertError};
+
+/// 계정의 온체인 nonce(다음 블록에서 실행될 nonce)를 알려주는 조회 인터페이스입니다.
+pub trait AccountNonceProvider {
+    /// 풀에 처음 등장한 sender마다 한 번 호출되며, 기록이 없는 계정은 0을 돌려줘야 합니다.
+    fn account_nonce(&self, sender: &Pubkey) -> u64;
+}
+
+/// 테스트나 단순 구성에서는 HashMap을 그대로 nonce 조회 테이블로 씁니다.
+impl AccountNonceProvider for HashMap<Pubkey, u64> {
+    fn account_nonce(&self, sender: &Pubkey) -> u64 {
+        self.get(sender).copied().unwrap_or(0)
+    }
+}
+
+/// 같은 상태를 nonce 조회기와 validator에 함께 넘길 수 있도록 Arc로 감싼 조회기도 그대로 씁니다.
+impl<T: AccountNonceProvider + ?Sized> AccountNonceProvider for Arc<T> {
+    fn account_nonce(&self, sender: &Pubkey) -> u64 {
+        (**self).account_nonce(sender)
+    }
+}
+
+/// 온체인 nonce와 잔고를 함께 알려주는 계정 상태 조회 인터페이스입니다.
+pub trait StateProvider: AccountNonceProvider {
+    /// 계정 잔고이며 기록이 없는 계정은 0을 돌려줘야 합니다.
+    fn balance(&self, sender: &Pubkey) -> u128;
+}
+
+impl<T: StateProvider + ?Sized> StateProvider for Arc<T> {
+    fn balance(&self, sender: &Pubkey) -> u128 {
+        (**self).balance(sender)
+    }
+}
+
+/// 테스트나 단순 구성에서 쓰는 메모리 상의 계정 상태입니다.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryStateProvider {
+    /// sender → `(nonce, balance)`
+    accounts: HashMap<Pubkey, (u64, u128)>,
+}
+
+impl InMemoryStateProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 계정의 nonce와 잔고를 기록합니다. 이미 있으면 덮어씁니다.
+    pub fn set_account(&mut self, sender: Pubkey, nonce: u64, balance: u128) {
+        self.accounts.insert(sender, (nonce, balance));
+    }
+}
+
+impl AccountNonceProvider for InMemoryStateProvider {
+    fn account_nonce(&self, sender: &Pubkey) -> u64 {
+        self.accounts.get(sender).map_or(0, |&(nonce, _)| nonce)
+    }
+}
+
+impl StateProvider for InMemoryStateProvider {
+    fn balance(&self, sender: &Pubkey) -> u128 {
+        self.accounts.get(sender).map_or(0, |&(_, balance)| balance)
+    }
+}
+
+/// insert가 풀 규칙(슬롯·capacity·교체)을 적용하기 전에 트랜잭션 자체를 검사하는 인터페이스입니다.
+pub trait TransactionValidator {
+    /// `pooled_cost`는 같은 sender가 이미 풀에 둔 트랜잭션의 `cost()` 합이며, 같은 nonce 교체 대상은 빠져 있습니다.
+    fn validate(&self, tx: &PendingTransaction, pooled_cost: u128) -> Result<(), TxInsertError>;
+}
+
+/// 아무것도 거부하지 않는 기본 validator입니다.
+pub struct NoopValidator;
+
+impl TransactionValidator for NoopValidator {
+    fn validate(&self, _tx: &PendingTransaction, _pooled_cost: u128) -> Result<(), TxInsertError> {
+        Ok(())
+    }
+}
+
+/// 계정 상태를 조회해 최소 팁, 온체인 nonce, 누적 비용 대비 잔고를 검사하는 validator입니다.
+pub struct StateValidator<S> {
+    state: S,
+    /// 이보다 낮은 팁 상한은 Underpriced로 거부합니다.
+    min_max_priority_fee_per_gas: u64,
+}
+
+impl<S: StateProvider> StateValidator<S> {
+    pub fn new(state: S, min_max_priority_fee_per_gas: u64) -> Self {
+        Self {
+            state,
+            min_max_priority_fee_per_gas,
+        }
+    }
+}
+
+impl<S: StateProvider> TransactionValidator for StateValidator<S> {
+    fn validate(&self, tx: &PendingTransaction, pooled_cost: u128) -> Result<(), TxInsertError> {
+        let (sender, nonce) = (tx.sender, tx.nonce);
+        if tx.max_priority_fee_per_gas < self.min_max_priority_fee_per_gas {
+            return Err(TxInsertError::Underpriced {
+                sender,
+                nonce,
+                min_max_priority_fee_per_gas: self.min_max_priority_fee_per_gas,
+            });
+        }
+        let state_nonce = self.state.account_nonce(&sender);
+        if nonce < state_nonce {
+            return Err(TxInsertError::NonceTooLow {
+                sender,
+                nonce,
+                state_nonce,
+            });
+        }
+        // 같은 sender 트랜잭션은 모두 같은 잔고에서 빠지므로, 하나씩은 감당해도 합계가 넘으면 뒤쪽은 실행될 수 없습니다.
+        let cost = pooled_cost.saturating_add(tx.cost());
+        let balance = self.state.balance(&sender);
+        if cost > balance {
+            return Err(TxInsertError::InsufficientFunds {
+                sender,
+                nonce,
+                cost,
+                balance,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use crate::{SubPool, TxPool, TxPoolConfig};
+
+    #[test]
+    fn state_validator_rejects_underpriced_and_stale_nonce() {
+        // Given: alice 온체인 nonce 3, 최소 팁 2, 같은 상태를 nonce 조회기와 validator가 공유
+        let mut state = InMemoryStateProvider::new();
+        state.set_account(Pubkey::from_name("alice"), 3, u128::MAX);
+        let state = Arc::new(state);
+        let mut pool = TxPool::with_nonce_provider(TxPoolConfig::default(), Arc::clone(&state))
+            .with_validator(StateValidator::new(state, 2));
+        // When: 팁 1, 이미 실행된 nonce 2, 정상 nonce 3 순서로 insert
+        let underpriced = pool.insert(make_1559_tx("alice", 3, 100, 1, "cheap"));
+        let stale = pool.insert(make_1559_tx("alice", 2, 100, 2, "stale"));
+        let outcome = pool.insert(make_1559_tx("alice", 3, 100, 2, "ok")).unwrap();
+        // Then: 각각 Underpriced, NonceTooLow로 거부되고 정상 건만 pending에 들어감
+        assert!(matches!(
+            underpriced,
+            Err(TxInsertError::Underpriced {
+                nonce: 3,
+                min_max_priority_fee_per_gas: 2,
+                ..
+            })
+        ));
+        assert!(matches!(
+            stale,
+            Err(TxInsertError::NonceTooLow {
+                nonce: 2,
+                state_nonce: 3,
+                ..
+            })
+        ));
+        assert_eq!(outcome.sub_pool, SubPool::Pending);
+        assert_eq!(pool.len(), 1);
+    }
+
+    #[test]
+    fn state_validator_checks_cumulative_cost_against_balance() {
+        // Given: 트랜잭션 하나 비용이 2_100_000(gas 100 × 21_000)이고, alice 잔고는 세 건 + 300_000
+        let per_tx = 100 * 21_000;
+        let balance = 3 * per_tx + 300_000;
+        let mut state = InMemoryStateProvider::new();
+        state.set_account(Pubkey::from_name("alice"), 0, balance);
+        let mut pool =
+            TxPool::new(TxPoolConfig::default()).with_validator(StateValidator::new(state, 0));
+        for nonce in 0..3 {
+            pool.insert(make_tx("alice", nonce, 0, 100, "a")).unwrap();
+        }
+        // When: 네 번째 트랜잭션 insert
+        let fourth = pool.insert(make_tx("alice", 3, 0, 100, "a"));
+        // Then: 한 건씩은 감당해도 합계가 잔고를 넘으므로 InsufficientFunds
+        assert!(matches!(
+            fourth,
+            Err(TxInsertError::InsufficientFunds { nonce: 3, cost, balance: reported, .. })
+                if cost == 4 * per_tx && reported == balance
+        ));
+        // When: nonce 2를 gas 110으로 교체 (밀려날 기존 비용은 합계에서 빠짐)
+        let replaced = pool.insert(make_tx("alice", 2, 0, 110, "bump"));
+        // Then: 2 × 2_100_000 + 2_310_000 ≤ 잔고라 교체는 허용
+        assert!(replaced.is_ok());
+        // When: 같은 nonce를 value 100_000을 얹어 다시 교체
+        let rich = PendingTransaction {
+            value: 100_000,
+            ..make_tx("alice", 2, 0, 121, "rich")
+        };
+        // Then: 2 × 2_100_000 + 2_541_000 + 100_000이 잔고를 넘어 거부되고 기존 교체 건이 남음
+        assert!(matches!(
+            pool.insert(rich),
+            Err(TxInsertError::InsufficientFunds { nonce: 2, .. })
+        ));
+        assert_eq!(
+            pool.per_account[&Pubkey::from_name("alice")].txs[&2].hash,
+            "alice-2-bump"
+        );
+    }
+}